@@ -0,0 +1,45 @@
+use anyhow::Result;
+use wstd::http::{body::IncomingBody, Method};
+
+use super::{S3RequestBuilder, S3RequestData, S3ResponseData};
+
+/// Abort a multipart upload and free the storage used by its parts
+pub struct AbortMultipartUploadRequest {
+    pub key: String,
+    pub upload_id: String,
+}
+
+impl S3RequestData for AbortMultipartUploadRequest {
+    type ResponseType = AbortMultipartUploadResponse;
+
+    fn into_builder(
+        &self,
+        access_key: &str,
+        secret_key: &str,
+        region: &str,
+        endpoint: &str,
+    ) -> Result<S3RequestBuilder<Self::ResponseType>> {
+        let mut builder = S3RequestBuilder::new(
+            Method::DELETE,
+            &self.key,
+            access_key,
+            secret_key,
+            region,
+            endpoint,
+        );
+        builder.query("uploadId", Some(&self.upload_id));
+
+        Ok(builder)
+    }
+}
+
+pub struct AbortMultipartUploadResponse {}
+
+impl S3ResponseData for AbortMultipartUploadResponse {
+    async fn parse_body(_response: &mut IncomingBody) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(Self {})
+    }
+}
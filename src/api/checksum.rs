@@ -0,0 +1,294 @@
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use wstd::http::HeaderMap;
+
+use super::{
+    checksum_algorithm_to_str,
+    x_amz_headers::{checksum_from_headers, checksum_to_header, XAmzChecksum},
+    ChecksumAlgorithm,
+};
+
+enum Hasher {
+    CRC32(crc32fast::Hasher),
+    CRC32C(u32),
+    CRC64NVME(crc64fast_nvme::Digest),
+    SHA1(Sha1),
+    SHA256(Sha256),
+}
+
+/// Compute a checksum incrementally, e.g. while a body is streamed
+///
+/// Only the algorithms supported by S3 can be computed, a
+/// [ChecksumAlgorithm::Alogrithm] is an error.
+pub struct ChecksumHasher {
+    hasher: Hasher,
+}
+
+impl ChecksumHasher {
+    pub fn new(algorithm: &ChecksumAlgorithm) -> Result<Self> {
+        let hasher = match algorithm {
+            ChecksumAlgorithm::CRC32 => Hasher::CRC32(crc32fast::Hasher::new()),
+            ChecksumAlgorithm::CRC32C => Hasher::CRC32C(0),
+            ChecksumAlgorithm::CRC64NVME => Hasher::CRC64NVME(crc64fast_nvme::Digest::new()),
+            ChecksumAlgorithm::SHA1 => Hasher::SHA1(Sha1::new()),
+            ChecksumAlgorithm::SHA256 => Hasher::SHA256(Sha256::new()),
+            ChecksumAlgorithm::Alogrithm(a) => {
+                return Err(anyhow!("can't compute unsupported checksum algorithm {a}"))
+            }
+        };
+
+        Ok(Self { hasher })
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match &mut self.hasher {
+            Hasher::CRC32(hasher) => hasher.update(data),
+            Hasher::CRC32C(crc) => *crc = crc32c::crc32c_append(*crc, data),
+            Hasher::CRC64NVME(digest) => digest.write(data),
+            Hasher::SHA1(hasher) => hasher.update(data),
+            Hasher::SHA256(hasher) => hasher.update(data),
+        }
+    }
+
+    /// The base64 encoded checksum
+    pub fn finalize(self) -> XAmzChecksum {
+        match self.hasher {
+            Hasher::CRC32(hasher) => {
+                XAmzChecksum::CRC32(BASE64_STANDARD.encode(hasher.finalize().to_be_bytes()))
+            }
+            Hasher::CRC32C(crc) => XAmzChecksum::CRC32C(BASE64_STANDARD.encode(crc.to_be_bytes())),
+            Hasher::CRC64NVME(digest) => {
+                XAmzChecksum::CRC64NVME(BASE64_STANDARD.encode(digest.sum64().to_be_bytes()))
+            }
+            Hasher::SHA1(hasher) => XAmzChecksum::SHA1(BASE64_STANDARD.encode(hasher.finalize())),
+            Hasher::SHA256(hasher) => {
+                XAmzChecksum::Sha256(BASE64_STANDARD.encode(hasher.finalize()))
+            }
+        }
+    }
+}
+
+/// Compute the checksum of `data`
+pub fn get_checksum(algorithm: &ChecksumAlgorithm, data: &[u8]) -> Result<XAmzChecksum> {
+    let mut hasher = ChecksumHasher::new(algorithm)?;
+    hasher.update(data);
+    Ok(hasher.finalize())
+}
+
+/// Compute the base64 encoded MD5 of `data` for the Content-MD5 header
+pub fn get_content_md5(data: &[u8]) -> String {
+    BASE64_STANDARD.encode(Md5::digest(data))
+}
+
+/// The x-amz-checksum-* header name of an algorithm
+pub(crate) fn get_checksum_header_name(algorithm: &ChecksumAlgorithm) -> String {
+    format!(
+        "x-amz-checksum-{}",
+        checksum_algorithm_to_str(algorithm).to_lowercase()
+    )
+}
+
+/// The length of the base64 encoded checksum
+pub(crate) fn get_checksum_encoded_length(algorithm: &ChecksumAlgorithm) -> Result<usize> {
+    let size: usize = match algorithm {
+        ChecksumAlgorithm::CRC32 | ChecksumAlgorithm::CRC32C => 4,
+        ChecksumAlgorithm::CRC64NVME => 8,
+        ChecksumAlgorithm::SHA1 => 20,
+        ChecksumAlgorithm::SHA256 => 32,
+        ChecksumAlgorithm::Alogrithm(a) => {
+            return Err(anyhow!("can't compute unsupported checksum algorithm {a}"))
+        }
+    };
+
+    Ok(size.div_ceil(3) * 4)
+}
+
+/// The algorithm of a checksum, `None` if it can't be computed
+fn get_checksum_algorithm(checksum: &XAmzChecksum) -> Option<ChecksumAlgorithm> {
+    match checksum {
+        XAmzChecksum::CRC32(_) => Some(ChecksumAlgorithm::CRC32),
+        XAmzChecksum::CRC32C(_) => Some(ChecksumAlgorithm::CRC32C),
+        XAmzChecksum::CRC64NVME(_) => Some(ChecksumAlgorithm::CRC64NVME),
+        XAmzChecksum::SHA1(_) => Some(ChecksumAlgorithm::SHA1),
+        XAmzChecksum::Sha256(_) => Some(ChecksumAlgorithm::SHA256),
+        XAmzChecksum::Checksum(..) => None,
+    }
+}
+
+/// The downloaded data doesn't match the checksum returned by S3
+///
+/// Returned as an [anyhow::Error] when a GetObject response with
+/// `x-amz-checksum-mode: ENABLED` fails verification, use
+/// `error.downcast_ref::<ChecksumMismatch>()` to tell it apart from other errors.
+#[derive(Debug)]
+pub struct ChecksumMismatch {
+    /// The x-amz-checksum-* header
+    pub header: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for ChecksumMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "checksum mismatch, {} is {} but the data has {}",
+            self.header, self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for ChecksumMismatch {}
+
+/// Verifies a response body against its x-amz-checksum-* header
+pub(crate) struct ChecksumValidator {
+    hasher: ChecksumHasher,
+    expected: XAmzChecksum,
+}
+
+impl ChecksumValidator {
+    /// Create a validator for the checksum in `headers`
+    ///
+    /// Returns `None` if there's no checksum or it can't be verified against
+    /// the body, composite checksums of multipart objects are checksums of
+    /// the part checksums.
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let is_composite = headers
+            .get("x-amz-checksum-type")
+            .is_some_and(|v| v.as_bytes().eq_ignore_ascii_case(b"COMPOSITE"));
+        let expected = checksum_from_headers(headers)?;
+        let (_, value) = checksum_to_header(&expected);
+        if is_composite || value.contains('-') {
+            return None;
+        }
+
+        let hasher = ChecksumHasher::new(&get_checksum_algorithm(&expected)?).ok()?;
+        Some(Self { hasher, expected })
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    pub(crate) fn verify(self) -> Result<(), ChecksumMismatch> {
+        let (header, expected) = checksum_to_header(&self.expected);
+        let (_, actual) = checksum_to_header(&self.hasher.finalize());
+        if expected != actual {
+            return Err(ChecksumMismatch {
+                header,
+                expected,
+                actual,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wstd::http::{HeaderName, HeaderValue};
+
+    use super::*;
+
+    // The check values of the algorithms are computed over "123456789"
+    const DATA: &[u8] = b"123456789";
+
+    fn get_header_checksum(algorithm: ChecksumAlgorithm) -> (String, String) {
+        checksum_to_header(&get_checksum(&algorithm, DATA).unwrap())
+    }
+
+    #[test]
+    fn checksums() {
+        let expected = [
+            (ChecksumAlgorithm::CRC32, "x-amz-checksum-crc32", "y/Q5Jg=="),
+            (
+                ChecksumAlgorithm::CRC32C,
+                "x-amz-checksum-crc32c",
+                "4waSgw==",
+            ),
+            (
+                ChecksumAlgorithm::CRC64NVME,
+                "x-amz-checksum-crc64nvme",
+                "rosUhgp5mIg=",
+            ),
+            (
+                ChecksumAlgorithm::SHA1,
+                "x-amz-checksum-sha1",
+                "98O8HYCOBHMq32eZZczDTKeuNEE=",
+            ),
+            (
+                ChecksumAlgorithm::SHA256,
+                "x-amz-checksum-sha256",
+                "FeKw08M4keuw8e9gnsQZQgwg4yDOlMZfvIwzEkSOsiU=",
+            ),
+        ];
+        for (algorithm, header, value) in expected {
+            assert_eq!(
+                get_checksum_encoded_length(&algorithm).unwrap(),
+                value.len()
+            );
+            assert_eq!(
+                get_header_checksum(algorithm),
+                (header.to_string(), value.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn incremental_update() {
+        let mut hasher = ChecksumHasher::new(&ChecksumAlgorithm::CRC32C).unwrap();
+        hasher.update(&DATA[..4]);
+        hasher.update(&DATA[4..]);
+        assert_eq!(
+            checksum_to_header(&hasher.finalize()).1,
+            get_header_checksum(ChecksumAlgorithm::CRC32C).1
+        );
+    }
+
+    #[test]
+    fn unsupported_algorithm() {
+        assert!(ChecksumHasher::new(&ChecksumAlgorithm::Alogrithm("XXHASH".to_string())).is_err());
+    }
+
+    #[test]
+    fn content_md5() {
+        assert_eq!(get_content_md5(DATA), "JfnnlDI7RTiF9RgfG2JNCw==");
+    }
+
+    fn get_validator(header: &str, value: &str) -> Option<ChecksumValidator> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            HeaderName::from_bytes(header.as_bytes()).unwrap(),
+            HeaderValue::from_str(value).unwrap(),
+        );
+        ChecksumValidator::from_headers(&headers)
+    }
+
+    #[test]
+    fn validator_match() {
+        let mut validator = get_validator("x-amz-checksum-crc32", "y/Q5Jg==").unwrap();
+        validator.update(DATA);
+        assert!(validator.verify().is_ok());
+    }
+
+    #[test]
+    fn validator_mismatch() {
+        let mut validator = get_validator("x-amz-checksum-crc32", "y/Q5Jg==").unwrap();
+        validator.update(b"12345678");
+        let mismatch = validator.verify().unwrap_err();
+        assert_eq!(mismatch.header, "x-amz-checksum-crc32");
+        assert_eq!(mismatch.expected, "y/Q5Jg==");
+        assert_ne!(mismatch.actual, mismatch.expected);
+    }
+
+    #[test]
+    fn validator_skips_composite_checksums() {
+        assert!(get_validator("x-amz-checksum-crc32", "y/Q5Jg==-2").is_none());
+    }
+}
@@ -0,0 +1,181 @@
+use anyhow::{anyhow, Result};
+use http::response::Parts;
+use wstd::{
+    http::{body::IncomingBody, Method},
+    io::AsyncRead,
+};
+use xml::{
+    reader::{EventReader, XmlEvent},
+    writer, EmitterConfig,
+};
+
+use super::{
+    checksum_type_from_str, parse_xml_checksum, parse_xml_string,
+    s3_error::S3Error,
+    write_xml_element,
+    x_amz_headers::{checksum_from_headers, XAmzChecksum},
+    ApiChecksumType, S3RequestBuilder, S3RequestData, S3ResponseData, S3_XML_NAMESPACE,
+    XML_CHECKSUM_FIELDS,
+};
+
+/// A part uploaded with [super::upload_part::UploadPartRequest]
+pub struct CompletedPart {
+    pub part_number: u32,
+    pub etag: String,
+    pub checksum: Option<XAmzChecksum>,
+}
+
+impl CompletedPart {
+    /// Create a completed part from the headers of an upload_part response
+    ///
+    /// see [super::S3Response::into_response_data_parts]
+    pub fn from_headers(part_number: u32, head: &Parts) -> Result<Self> {
+        let etag = match head.headers.get("etag") {
+            Some(etag) => etag.to_str()?.to_owned(),
+            None => return Err(anyhow!("Invalid response, ETag header is missing")),
+        };
+
+        Ok(Self {
+            part_number,
+            etag,
+            checksum: checksum_from_headers(&head.headers),
+        })
+    }
+}
+
+/// Complete a multipart upload by assembling the uploaded parts
+pub struct CompleteMultipartUploadRequest {
+    pub key: String,
+    pub upload_id: String,
+    pub parts: Vec<CompletedPart>,
+}
+
+impl CompleteMultipartUploadRequest {
+    fn get_body(&self) -> Result<Vec<u8>> {
+        let mut body = Vec::new();
+        let mut writer = EmitterConfig::new()
+            .write_document_declaration(false)
+            .create_writer(&mut body);
+
+        writer.write(
+            writer::XmlEvent::start_element("CompleteMultipartUpload").default_ns(S3_XML_NAMESPACE),
+        )?;
+        for part in &self.parts {
+            writer.write(writer::XmlEvent::start_element("Part"))?;
+            if let Some(checksum) = &part.checksum {
+                let (field, sum) = match checksum {
+                    XAmzChecksum::CRC32(sum) => ("ChecksumCRC32".to_string(), sum),
+                    XAmzChecksum::CRC32C(sum) => ("ChecksumCRC32C".to_string(), sum),
+                    XAmzChecksum::CRC64NVME(sum) => ("ChecksumCRC64NVME".to_string(), sum),
+                    XAmzChecksum::SHA1(sum) => ("ChecksumSHA1".to_string(), sum),
+                    XAmzChecksum::Sha256(sum) => ("ChecksumSHA256".to_string(), sum),
+                    XAmzChecksum::Checksum(k, sum) => {
+                        (format!("Checksum{}", k.to_uppercase()), sum)
+                    }
+                };
+                write_xml_element(&mut writer, &field, sum)?;
+            }
+            write_xml_element(&mut writer, "ETag", &part.etag)?;
+            write_xml_element(&mut writer, "PartNumber", &part.part_number.to_string())?;
+            writer.write(writer::XmlEvent::end_element())?;
+        }
+        writer.write(writer::XmlEvent::end_element())?;
+
+        Ok(body)
+    }
+}
+
+impl S3RequestData for CompleteMultipartUploadRequest {
+    type ResponseType = CompleteMultipartUploadResponse;
+
+    fn into_builder(
+        &self,
+        access_key: &str,
+        secret_key: &str,
+        region: &str,
+        endpoint: &str,
+    ) -> Result<S3RequestBuilder<Self::ResponseType>> {
+        if self.parts.is_empty() {
+            return Err(anyhow!("parts has to contain at least one part"));
+        }
+
+        let mut builder = S3RequestBuilder::new(
+            Method::POST,
+            &self.key,
+            access_key,
+            secret_key,
+            region,
+            endpoint,
+        );
+        builder.query("uploadId", Some(&self.upload_id));
+        builder.body(self.get_body()?);
+
+        Ok(builder)
+    }
+}
+
+pub struct CompleteMultipartUploadResponse {
+    pub location: Option<String>,
+    pub bucket: String,
+    pub key: String,
+    pub etag: String,
+    pub checksum: Option<XAmzChecksum>,
+    pub checksum_type: Option<ApiChecksumType>,
+}
+
+impl S3ResponseData for CompleteMultipartUploadResponse {
+    async fn parse_body(response: &mut IncomingBody) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut data = Vec::<u8>::new();
+        response.read_to_end(&mut data).await?;
+        // S3 can fail the upload after sending the 200 status
+        if let Some(error) = S3Error::from_ok_body(&data) {
+            return Err(error.into());
+        }
+        let mut parser = EventReader::new(data.as_slice());
+
+        let mut complete_response = CompleteMultipartUploadResponse {
+            location: None,
+            bucket: String::new(),
+            key: String::new(),
+            etag: String::new(),
+            checksum: None,
+            checksum_type: None,
+        };
+        loop {
+            match parser.next()? {
+                XmlEvent::EndDocument => break,
+
+                XmlEvent::StartElement { name, .. } if name.local_name == "Location" => {
+                    complete_response.location = Some(parse_xml_string(&mut parser, "Location")?);
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "Bucket" => {
+                    complete_response.bucket = parse_xml_string(&mut parser, "Bucket")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "Key" => {
+                    complete_response.key = parse_xml_string(&mut parser, "Key")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "ETag" => {
+                    complete_response.etag = parse_xml_string(&mut parser, "ETag")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "ChecksumType" => {
+                    complete_response.checksum_type = Some(checksum_type_from_str(
+                        &parse_xml_string(&mut parser, "ChecksumType")?,
+                    )?);
+                }
+                XmlEvent::StartElement { name, .. }
+                    if XML_CHECKSUM_FIELDS.contains(&name.local_name.as_str()) =>
+                {
+                    complete_response.checksum =
+                        Some(parse_xml_checksum(&mut parser, &name.local_name)?);
+                }
+
+                _ => {}
+            }
+        }
+
+        Ok(complete_response)
+    }
+}
@@ -65,4 +65,14 @@ impl ConditionalHeaders {
 
         headers
     }
+
+    /// The same conditions applied to the source object of a copy
+    ///
+    /// see [super::copy_object::CopyObjectRequest]
+    pub(crate) fn get_copy_source_headers(&self) -> Vec<(String, String)> {
+        self.get_headers()
+            .into_iter()
+            .map(|(key, value)| (format!("x-amz-copy-source-{}", key.to_lowercase()), value))
+            .collect()
+    }
 }
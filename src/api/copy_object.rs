@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use wstd::{
+    http::{body::IncomingBody, Method},
+    io::AsyncRead,
+};
+use xml::reader::{EventReader, XmlEvent};
+
+use super::{
+    checksum_type_from_str, conditional_headers::ConditionalHeaders, parse_xml_checksum,
+    parse_xml_string, percent_encode_path, percent_encode_query, s3_error::S3Error,
+    user_metadata::get_metadata_headers, x_amz_headers::XAmzChecksum, ApiChecksumType,
+    S3RequestBuilder, S3RequestData, S3ResponseData, XML_CHECKSUM_FIELDS,
+};
+
+/// The object to copy from
+pub struct CopySource {
+    pub bucket: String,
+    pub key: String,
+    pub version_id: Option<String>,
+}
+
+impl CopySource {
+    pub fn new(bucket: &str, key: &str) -> Self {
+        Self {
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+            version_id: None,
+        }
+    }
+
+    /// The x-amz-copy-source header value, /{bucket}/{key}?versionId={version_id}
+    pub(crate) fn get_header_value(&self) -> String {
+        let mut source = format!(
+            "/{}/{}",
+            percent_encode_path(&self.bucket),
+            percent_encode_path(self.key.trim_start_matches('/'))
+        );
+        if let Some(version_id) = &self.version_id {
+            source.push_str(&format!("?versionId={}", percent_encode_query(version_id)));
+        }
+
+        source
+    }
+}
+
+pub enum MetadataDirective {
+    Copy,
+    Replace,
+}
+
+pub enum TaggingDirective {
+    Copy,
+    Replace,
+}
+
+/// Copy an object server side
+///
+/// Objects larger than 5 GiB have to be copied with
+/// [super::upload_part_copy::UploadPartCopyRequest]
+pub struct CopyObjectRequest {
+    pub key: String,
+    pub source: CopySource,
+    pub metadata_directive: Option<MetadataDirective>,
+    pub tagging_directive: Option<TaggingDirective>,
+    /// Conditions on the source object, sent as x-amz-copy-source-if-* headers
+    pub source_conditions: Option<ConditionalHeaders>,
+    /// x-amz-meta-* user metadata, requires [MetadataDirective::Replace]
+    pub metadata: HashMap<String, String>,
+}
+
+impl CopyObjectRequest {
+    pub fn new(key: &str, source: CopySource) -> Self {
+        Self {
+            key: key.to_owned(),
+            source,
+            metadata_directive: None,
+            tagging_directive: None,
+            source_conditions: None,
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+impl S3RequestData for CopyObjectRequest {
+    type ResponseType = CopyObjectResponse;
+
+    fn into_builder(
+        &self,
+        access_key: &str,
+        secret_key: &str,
+        region: &str,
+        endpoint: &str,
+    ) -> Result<S3RequestBuilder<Self::ResponseType>> {
+        let mut builder = S3RequestBuilder::new(
+            Method::PUT,
+            &self.key,
+            access_key,
+            secret_key,
+            region,
+            endpoint,
+        );
+        builder.header("x-amz-copy-source", &self.source.get_header_value());
+
+        if let Some(directive) = &self.metadata_directive {
+            let d = match directive {
+                MetadataDirective::Copy => "COPY",
+                MetadataDirective::Replace => "REPLACE",
+            };
+            builder.header("x-amz-metadata-directive", d);
+        }
+        if let Some(directive) = &self.tagging_directive {
+            let d = match directive {
+                TaggingDirective::Copy => "COPY",
+                TaggingDirective::Replace => "REPLACE",
+            };
+            builder.header("x-amz-tagging-directive", d);
+        }
+        if let Some(conditions) = &self.source_conditions {
+            builder.headers(conditions.get_copy_source_headers());
+        }
+        if !self.metadata.is_empty() {
+            // S3 silently keeps the source metadata unless it's replaced
+            if !matches!(self.metadata_directive, Some(MetadataDirective::Replace)) {
+                return Err(anyhow!(
+                    "metadata is only set with metadata_directive MetadataDirective::Replace"
+                ));
+            }
+            builder.headers(get_metadata_headers(&self.metadata)?);
+        }
+
+        Ok(builder)
+    }
+}
+
+pub struct CopyObjectResponse {
+    pub etag: String,
+    pub last_modified: Option<DateTime<Utc>>,
+    pub checksum: Option<XAmzChecksum>,
+    pub checksum_type: Option<ApiChecksumType>,
+}
+
+impl S3ResponseData for CopyObjectResponse {
+    async fn parse_body(response: &mut IncomingBody) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut data = Vec::<u8>::new();
+        response.read_to_end(&mut data).await?;
+        // S3 can fail the copy after sending the 200 status
+        if let Some(error) = S3Error::from_ok_body(&data) {
+            return Err(error.into());
+        }
+        let mut parser = EventReader::new(data.as_slice());
+
+        let mut copy_response = CopyObjectResponse {
+            etag: String::new(),
+            last_modified: None,
+            checksum: None,
+            checksum_type: None,
+        };
+        loop {
+            match parser.next()? {
+                XmlEvent::EndDocument => break,
+
+                XmlEvent::StartElement { name, .. } if name.local_name == "ETag" => {
+                    copy_response.etag = parse_xml_string(&mut parser, "ETag")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "LastModified" => {
+                    let datetime = DateTime::parse_from_rfc3339(&parse_xml_string(
+                        &mut parser,
+                        "LastModified",
+                    )?)?
+                    .to_utc();
+                    copy_response.last_modified = Some(datetime);
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "ChecksumType" => {
+                    copy_response.checksum_type = Some(checksum_type_from_str(&parse_xml_string(
+                        &mut parser,
+                        "ChecksumType",
+                    )?)?);
+                }
+                XmlEvent::StartElement { name, .. }
+                    if XML_CHECKSUM_FIELDS.contains(&name.local_name.as_str()) =>
+                {
+                    copy_response.checksum =
+                        Some(parse_xml_checksum(&mut parser, &name.local_name)?);
+                }
+
+                _ => {}
+            }
+        }
+
+        if copy_response.etag.is_empty() {
+            return Err(anyhow!("Invalid response object, ETag is missing"));
+        }
+
+        Ok(copy_response)
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use wstd::{
+    http::{body::IncomingBody, Method},
+    io::AsyncRead,
+};
+use xml::reader::{EventReader, XmlEvent};
+
+use super::{
+    checksum_algorithm_to_str, checksum_type_to_str, parse_xml_string,
+    user_metadata::get_metadata_headers, ApiChecksumType, ChecksumAlgorithm, S3RequestBuilder,
+    S3RequestData, S3ResponseData,
+};
+
+/// Initiate a multipart upload
+///
+/// The returned upload id is used by [super::upload_part::UploadPartRequest],
+/// [super::complete_multipart_upload::CompleteMultipartUploadRequest] and
+/// [super::abort_multipart_upload::AbortMultipartUploadRequest].
+pub struct CreateMultipartUploadRequest {
+    pub key: String,
+    pub checksum_algorithm: Option<ChecksumAlgorithm>,
+    pub checksum_type: Option<ApiChecksumType>,
+    /// x-amz-meta-* user metadata of the completed object
+    pub metadata: HashMap<String, String>,
+}
+
+impl CreateMultipartUploadRequest {
+    pub fn from_key(key: &str) -> Self {
+        Self {
+            key: key.to_owned(),
+            checksum_algorithm: None,
+            checksum_type: None,
+            metadata: HashMap::new(),
+        }
+    }
+}
+
+impl S3RequestData for CreateMultipartUploadRequest {
+    type ResponseType = CreateMultipartUploadResponse;
+
+    fn into_builder(
+        &self,
+        access_key: &str,
+        secret_key: &str,
+        region: &str,
+        endpoint: &str,
+    ) -> Result<S3RequestBuilder<Self::ResponseType>> {
+        let mut builder = S3RequestBuilder::new(
+            Method::POST,
+            &self.key,
+            access_key,
+            secret_key,
+            region,
+            endpoint,
+        );
+        builder.query("uploads", None);
+
+        if let Some(algorithm) = &self.checksum_algorithm {
+            builder.header(
+                "x-amz-checksum-algorithm",
+                &checksum_algorithm_to_str(algorithm),
+            );
+        }
+        if let Some(checksum_type) = &self.checksum_type {
+            builder.header("x-amz-checksum-type", checksum_type_to_str(checksum_type));
+        }
+        builder.headers(get_metadata_headers(&self.metadata)?);
+
+        Ok(builder)
+    }
+}
+
+pub struct CreateMultipartUploadResponse {
+    pub bucket: String,
+    pub key: String,
+    pub upload_id: String,
+}
+
+impl S3ResponseData for CreateMultipartUploadResponse {
+    async fn parse_body(response: &mut IncomingBody) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut data = Vec::<u8>::new();
+        response.read_to_end(&mut data).await?;
+        let mut parser = EventReader::new(data.as_slice());
+
+        let mut create_response = CreateMultipartUploadResponse {
+            bucket: String::new(),
+            key: String::new(),
+            upload_id: String::new(),
+        };
+        loop {
+            match parser.next()? {
+                XmlEvent::EndDocument => break,
+
+                XmlEvent::StartElement { name, .. } if name.local_name == "Bucket" => {
+                    create_response.bucket = parse_xml_string(&mut parser, "Bucket")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "Key" => {
+                    create_response.key = parse_xml_string(&mut parser, "Key")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "UploadId" => {
+                    create_response.upload_id = parse_xml_string(&mut parser, "UploadId")?;
+                }
+
+                _ => {}
+            }
+        }
+
+        Ok(create_response)
+    }
+}
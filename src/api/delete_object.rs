@@ -0,0 +1,68 @@
+use anyhow::Result;
+use wstd::http::{body::IncomingBody, Method};
+
+use super::{S3RequestBuilder, S3RequestData, S3ResponseData};
+
+pub struct DeleteObjectRequest {
+    pub key: String,
+    pub version_id: Option<String>,
+    /// The concatenation of the authentication device's serial number, a
+    /// space, and the value displayed on the device
+    pub mfa: Option<String>,
+    pub bypass_governance_retention: bool,
+}
+
+impl DeleteObjectRequest {
+    pub fn from_key(key: &str) -> Self {
+        Self {
+            key: key.to_owned(),
+            version_id: None,
+            mfa: None,
+            bypass_governance_retention: false,
+        }
+    }
+}
+
+impl S3RequestData for DeleteObjectRequest {
+    type ResponseType = DeleteObjectResponse;
+
+    fn into_builder(
+        &self,
+        access_key: &str,
+        secret_key: &str,
+        region: &str,
+        endpoint: &str,
+    ) -> Result<S3RequestBuilder<Self::ResponseType>> {
+        let mut builder = S3RequestBuilder::new(
+            Method::DELETE,
+            &self.key,
+            access_key,
+            secret_key,
+            region,
+            endpoint,
+        );
+
+        if let Some(version_id) = &self.version_id {
+            builder.query("versionId", Some(version_id));
+        }
+        if let Some(mfa) = &self.mfa {
+            builder.header("x-amz-mfa", mfa);
+        }
+        if self.bypass_governance_retention {
+            builder.header("x-amz-bypass-governance-retention", "true");
+        }
+
+        Ok(builder)
+    }
+}
+
+pub struct DeleteObjectResponse {}
+
+impl S3ResponseData for DeleteObjectResponse {
+    async fn parse_body(_response: &mut IncomingBody) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(Self {})
+    }
+}
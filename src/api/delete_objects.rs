@@ -0,0 +1,227 @@
+use anyhow::{anyhow, Result};
+use wstd::{
+    http::{body::IncomingBody, Method},
+    io::AsyncRead,
+};
+use xml::{
+    reader::{EventReader, XmlEvent},
+    writer, EmitterConfig,
+};
+
+use super::{
+    parse_xml_bool, parse_xml_optional_string, parse_xml_string, write_xml_element,
+    S3RequestBuilder, S3RequestData, S3ResponseData, S3_XML_NAMESPACE,
+};
+
+const MAX_DELETE_OBJECTS: usize = 1000;
+
+pub struct ObjectIdentifier {
+    pub key: String,
+    pub version_id: Option<String>,
+}
+
+/// Delete up to 1000 objects in a single request
+pub struct DeleteObjectsRequest {
+    pub objects: Vec<ObjectIdentifier>,
+    /// Only return the keys that failed to be deleted
+    pub quiet: bool,
+    pub mfa: Option<String>,
+    pub bypass_governance_retention: bool,
+}
+
+impl DeleteObjectsRequest {
+    pub fn from_keys(keys: &[&str]) -> Self {
+        Self {
+            objects: keys
+                .iter()
+                .map(|key| ObjectIdentifier {
+                    key: key.to_string(),
+                    version_id: None,
+                })
+                .collect(),
+            quiet: false,
+            mfa: None,
+            bypass_governance_retention: false,
+        }
+    }
+
+    fn get_body(&self) -> Result<Vec<u8>> {
+        let mut body = Vec::new();
+        let mut writer = EmitterConfig::new()
+            .write_document_declaration(false)
+            .create_writer(&mut body);
+
+        writer.write(writer::XmlEvent::start_element("Delete").default_ns(S3_XML_NAMESPACE))?;
+        for object in &self.objects {
+            writer.write(writer::XmlEvent::start_element("Object"))?;
+            write_xml_element(&mut writer, "Key", &object.key)?;
+            if let Some(version_id) = &object.version_id {
+                write_xml_element(&mut writer, "VersionId", version_id)?;
+            }
+            writer.write(writer::XmlEvent::end_element())?;
+        }
+        if self.quiet {
+            write_xml_element(&mut writer, "Quiet", "true")?;
+        }
+        writer.write(writer::XmlEvent::end_element())?;
+
+        Ok(body)
+    }
+}
+
+impl S3RequestData for DeleteObjectsRequest {
+    type ResponseType = DeleteObjectsResponse;
+
+    fn into_builder(
+        &self,
+        access_key: &str,
+        secret_key: &str,
+        region: &str,
+        endpoint: &str,
+    ) -> Result<S3RequestBuilder<Self::ResponseType>> {
+        if self.objects.is_empty() || self.objects.len() > MAX_DELETE_OBJECTS {
+            return Err(anyhow!(
+                "objects has to be constrained to objects >= 1 and objects <= {MAX_DELETE_OBJECTS}, objects is {}",
+                self.objects.len()
+            ));
+        }
+
+        let mut builder =
+            S3RequestBuilder::new(Method::POST, "/", access_key, secret_key, region, endpoint);
+        builder.query("delete", None);
+
+        if let Some(mfa) = &self.mfa {
+            builder.header("x-amz-mfa", mfa);
+        }
+        if self.bypass_governance_retention {
+            builder.header("x-amz-bypass-governance-retention", "true");
+        }
+
+        // DeleteObjects requires Content-MD5 or an x-amz-checksum header
+        builder.body(self.get_body()?);
+        builder.content_md5();
+
+        Ok(builder)
+    }
+}
+
+pub struct DeletedObject {
+    pub key: String,
+    pub version_id: Option<String>,
+    pub delete_marker: bool,
+    pub delete_marker_version_id: Option<String>,
+}
+
+impl DeletedObject {
+    fn parse(parser: &mut EventReader<&[u8]>) -> Result<Self> {
+        let mut deleted = Self {
+            key: String::new(),
+            version_id: None,
+            delete_marker: false,
+            delete_marker_version_id: None,
+        };
+        loop {
+            match parser.next()? {
+                XmlEvent::StartElement { name, .. } if name.local_name == "Key" => {
+                    deleted.key = parse_xml_string(parser, "Key")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "VersionId" => {
+                    deleted.version_id = parse_xml_optional_string(parser, "VersionId")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "DeleteMarker" => {
+                    deleted.delete_marker = parse_xml_bool(parser, "DeleteMarker")?;
+                }
+                XmlEvent::StartElement { name, .. }
+                    if name.local_name == "DeleteMarkerVersionId" =>
+                {
+                    deleted.delete_marker_version_id =
+                        parse_xml_optional_string(parser, "DeleteMarkerVersionId")?;
+                }
+                XmlEvent::EndElement { name } if name.local_name == "Deleted" => break,
+                _ => {}
+            }
+        }
+
+        Ok(deleted)
+    }
+}
+
+pub struct DeleteObjectError {
+    pub key: String,
+    pub version_id: Option<String>,
+    pub code: String,
+    pub message: String,
+}
+
+impl DeleteObjectError {
+    fn parse(parser: &mut EventReader<&[u8]>) -> Result<Self> {
+        let mut error = Self {
+            key: String::new(),
+            version_id: None,
+            code: String::new(),
+            message: String::new(),
+        };
+        loop {
+            match parser.next()? {
+                XmlEvent::StartElement { name, .. } if name.local_name == "Key" => {
+                    error.key = parse_xml_string(parser, "Key")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "VersionId" => {
+                    error.version_id = parse_xml_optional_string(parser, "VersionId")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "Code" => {
+                    error.code = parse_xml_string(parser, "Code")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "Message" => {
+                    error.message =
+                        parse_xml_optional_string(parser, "Message")?.unwrap_or_default();
+                }
+                XmlEvent::EndElement { name } if name.local_name == "Error" => break,
+                _ => {}
+            }
+        }
+
+        Ok(error)
+    }
+}
+
+pub struct DeleteObjectsResponse {
+    pub deleted: Vec<DeletedObject>,
+    pub errors: Vec<DeleteObjectError>,
+}
+
+impl S3ResponseData for DeleteObjectsResponse {
+    async fn parse_body(response: &mut IncomingBody) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut data = Vec::<u8>::new();
+        response.read_to_end(&mut data).await?;
+        let mut parser = EventReader::new(data.as_slice());
+
+        let mut delete_response = DeleteObjectsResponse {
+            deleted: Vec::new(),
+            errors: Vec::new(),
+        };
+        loop {
+            match parser.next()? {
+                XmlEvent::EndDocument => break,
+
+                XmlEvent::StartElement { name, .. } if name.local_name == "Deleted" => {
+                    delete_response
+                        .deleted
+                        .push(DeletedObject::parse(&mut parser)?);
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "Error" => {
+                    delete_response
+                        .errors
+                        .push(DeleteObjectError::parse(&mut parser)?);
+                }
+
+                _ => {}
+            }
+        }
+
+        Ok(delete_response)
+    }
+}
@@ -1,15 +1,37 @@
+use std::{collections::HashMap, fs::File, io::Write, path::Path};
+
 use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use http::response::Parts;
 use wstd::{
-    http::{body::IncomingBody, Method},
-    io::AsyncRead,
+    http::{body::IncomingBody, HeaderMap, Method},
+    io::{self, AsyncInputStream, AsyncRead, AsyncWrite},
+};
+
+use super::{
+    checksum::{ChecksumMismatch, ChecksumValidator},
+    checksum_type_from_str,
+    response_headers::{
+        get_expiration, get_header, get_header_date, get_header_value, get_user_metadata,
+        ObjectExpiration,
+    },
+    s3_error::S3Error,
+    x_amz_headers::{
+        checksum_from_headers, server_side_encryption_from_str, XAmzChecksum,
+        XAmzServerSideEncryption,
+    },
+    ApiChecksumType, S3RequestBuilder, S3RequestData, S3Response, S3ResponseData,
 };
 
-use super::{S3RequestBuilder, S3RequestData, S3ResponseData};
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
 
 pub struct GetObjectRequest {
     pub key: String,
     pub part_number: Option<i32>,
     pub version_id: Option<String>,
+    /// Send `x-amz-checksum-mode: ENABLED` and verify the object against the
+    /// returned checksum
+    pub checksum_mode: bool,
 }
 
 impl GetObjectRequest {
@@ -18,6 +40,7 @@ impl GetObjectRequest {
             key: key.to_owned(),
             part_number: None,
             version_id: None,
+            checksum_mode: false,
         }
     }
 }
@@ -51,22 +74,253 @@ impl S3RequestData for GetObjectRequest {
         if let Some(version_id) = &self.version_id {
             builder.query("versionId", Some(version_id));
         }
+        if self.checksum_mode {
+            builder.header("x-amz-checksum-mode", "ENABLED");
+        }
 
         Ok(builder)
     }
 }
 
+/// The object buffered in memory
+///
+/// Use [S3Response::into_stream] for large objects.
+/// When the request enables `x-amz-checksum-mode` the object is verified
+/// against the returned checksum, a mismatch is a [ChecksumMismatch] error.
 pub struct GetObjectResponse {
     pub data: Vec<u8>,
+    pub content_type: Option<String>,
+    /// The returned range of the object for range and part requests
+    pub content_range: Option<String>,
+    pub etag: Option<String>,
+    pub last_modified: Option<DateTime<Utc>>,
+    pub version_id: Option<String>,
+    /// Only returned if the request enables `x-amz-checksum-mode`
+    pub checksum: Option<XAmzChecksum>,
+    pub checksum_type: Option<ApiChecksumType>,
+    pub server_side_encryption: Option<XAmzServerSideEncryption>,
+    pub kms_key_id: Option<String>,
+    pub bucket_key_enabled: bool,
+    pub sse_customer_algorithm: Option<String>,
+    pub sse_customer_key_md5: Option<String>,
+    pub expiration: Option<ObjectExpiration>,
+    /// The x-amz-meta-* user metadata without the prefix
+    pub metadata: HashMap<String, String>,
+}
+
+impl GetObjectResponse {
+    /// Verify the object against the checksum of the headers if there is one
+    fn from_parts(data: Vec<u8>, headers: &HeaderMap) -> Result<Self> {
+        if let Some(mut validator) = ChecksumValidator::from_headers(headers) {
+            validator.update(&data);
+            validator.verify()?;
+        }
+
+        Ok(Self {
+            data,
+            content_type: get_header(headers, "content-type"),
+            content_range: get_header(headers, "content-range"),
+            etag: get_header(headers, "etag"),
+            last_modified: get_header_date(headers, "last-modified"),
+            version_id: get_header(headers, "x-amz-version-id"),
+            checksum: checksum_from_headers(headers),
+            checksum_type: get_header(headers, "x-amz-checksum-type")
+                .and_then(|t| checksum_type_from_str(&t).ok()),
+            server_side_encryption: get_header(headers, "x-amz-server-side-encryption")
+                .map(|a| server_side_encryption_from_str(&a)),
+            kms_key_id: get_header(headers, "x-amz-server-side-encryption-aws-kms-key-id"),
+            bucket_key_enabled: get_header_value(
+                headers,
+                "x-amz-server-side-encryption-bucket-key-enabled",
+            )
+            .unwrap_or(false),
+            sse_customer_algorithm: get_header(
+                headers,
+                "x-amz-server-side-encryption-customer-algorithm",
+            ),
+            sse_customer_key_md5: get_header(
+                headers,
+                "x-amz-server-side-encryption-customer-key-md5",
+            ),
+            expiration: get_expiration(headers),
+            metadata: get_user_metadata(headers),
+        })
+    }
 }
 
 impl S3ResponseData for GetObjectResponse {
-    async fn parse_body(response: &mut IncomingBody) -> anyhow::Result<Self>
+    /// Without the response headers only `data` is set
+    async fn parse_body(response: &mut IncomingBody) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut data = Vec::<u8>::new();
+        response.read_to_end(&mut data).await?;
+
+        Self::from_parts(data, &HeaderMap::new())
+    }
+
+    async fn parse_response(head: &Parts, response: &mut IncomingBody) -> Result<Self>
     where
         Self: Sized,
     {
         let mut data = Vec::<u8>::new();
         response.read_to_end(&mut data).await?;
-        Ok(Self { data })
+
+        Self::from_parts(data, &head.headers)
+    }
+}
+
+impl S3Response<GetObjectResponse> {
+    /// Stream the object instead of buffering it in memory
+    ///
+    /// Returns an [S3Error] for non-2xx responses
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use s3_wasi_http::{S3Client, api::get_object::GetObjectRequest};
+    /// # async fn example() -> anyhow::Result<()> {
+    /// let client = S3Client::new_from_env();
+    ///
+    /// let stream = client
+    ///     .get_object(GetObjectRequest::from_key("myobject"))
+    ///     .await?
+    ///     .into_stream()
+    ///     .await?;
+    /// stream.copy_to(wstd::io::stdout()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn into_stream(mut self) -> Result<GetObjectStream> {
+        if !self.head.status.is_success() {
+            return Err(S3Error::from_response(&self.head, &mut self.body)
+                .await?
+                .into());
+        }
+
+        Ok(GetObjectStream {
+            validator: ChecksumValidator::from_headers(&self.head.headers),
+            head: self.head,
+            body: self.body,
+        })
+    }
+}
+
+/// A streamed GetObject response body
+///
+/// Use [GetObjectStream::next_chunk] to read the object in chunks or use it
+/// as an [AsyncRead], [GetObjectStream::copy_to] and
+/// [GetObjectStream::write_to_file] copy the object without buffering it.
+///
+/// When the request enables `x-amz-checksum-mode` the object is verified while
+/// it's read, the end of the body is a [ChecksumMismatch] error on mismatch.
+pub struct GetObjectStream {
+    head: Parts,
+    body: IncomingBody,
+    validator: Option<ChecksumValidator>,
+}
+
+impl GetObjectStream {
+    /// The response status and headers
+    pub fn head(&self) -> &Parts {
+        &self.head
+    }
+
+    /// The size of the object or range from the content-length header
+    pub fn content_length(&self) -> Option<u64> {
+        self.head
+            .headers
+            .get("content-length")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+    }
+
+    /// Read the next chunk of at most 64 KiB, `None` when the body is done
+    pub async fn next_chunk(&mut self) -> Result<Option<Vec<u8>>> {
+        let mut chunk = vec![0u8; STREAM_CHUNK_SIZE];
+        let len = self.read(&mut chunk).await.map_err(from_io_error)?;
+        if len == 0 {
+            return Ok(None);
+        }
+
+        chunk.truncate(len);
+        Ok(Some(chunk))
+    }
+
+    /// Copy the body to a writer, e.g. a WASI output stream
+    ///
+    /// The body is spliced directly into WASI output streams unless it's
+    /// verified against a checksum.
+    pub async fn copy_to<W>(self, writer: W) -> Result<()>
+    where
+        W: AsyncWrite,
+    {
+        io::copy(self, writer).await.map_err(from_io_error)
+    }
+
+    /// Write the body to a file, returns the number of bytes written
+    ///
+    /// The file is written with the blocking [std::fs::File], WASI file
+    /// writes block the component until they're done.
+    pub async fn write_to_file<P>(mut self, path: P) -> Result<u64>
+    where
+        P: AsRef<Path>,
+    {
+        let mut file = File::create(path)?;
+        let mut written = 0;
+        while let Some(chunk) = self.next_chunk().await? {
+            file.write_all(&chunk)?;
+            written += chunk.len() as u64;
+        }
+        file.flush()?;
+
+        Ok(written)
+    }
+
+    /// Get the body as an [AsyncRead], the body isn't verified
+    pub fn into_body(self) -> IncomingBody {
+        self.body
+    }
+}
+
+/// Get the [ChecksumMismatch] back out of an io error from [GetObjectStream::read]
+fn from_io_error(error: io::Error) -> anyhow::Error {
+    if !error.get_ref().is_some_and(|e| e.is::<ChecksumMismatch>()) {
+        return error.into();
+    }
+
+    match error.into_inner().map(|e| e.downcast::<ChecksumMismatch>()) {
+        Some(Ok(mismatch)) => (*mismatch).into(),
+        _ => anyhow!("checksum mismatch"),
+    }
+}
+
+impl AsyncRead for GetObjectStream {
+    /// Read from the body, verifying the checksum at the end of the body
+    async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // A read into an empty buffer returns 0 without being the end of the body
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let len = self.body.read(buf).await?;
+        match (len, self.validator.take()) {
+            (0, Some(validator)) => validator.verify().map_err(io::Error::other)?,
+            (_, Some(mut validator)) => {
+                validator.update(&buf[..len]);
+                self.validator = Some(validator);
+            }
+            (_, None) => {}
+        }
+
+        Ok(len)
+    }
+
+    fn as_async_input_stream(&self) -> Option<&AsyncInputStream> {
+        // Splicing the body would skip the verification
+        match self.validator {
+            Some(_) => None,
+            None => self.body.as_async_input_stream(),
+        }
     }
 }
@@ -1,7 +1,23 @@
+use std::collections::HashMap;
+
 use anyhow::{anyhow, Result};
-use wstd::http::{body::IncomingBody, Method};
+use chrono::{DateTime, Utc};
+use http::response::Parts;
+use wstd::http::{body::IncomingBody, HeaderMap, Method};
 
-use super::{S3RequestBuilder, S3RequestData, S3ResponseData};
+use super::{
+    checksum_type_from_str,
+    response_headers::{
+        get_expiration, get_header, get_header_date, get_header_value, get_restore_status,
+        get_user_metadata, ObjectExpiration,
+    },
+    x_amz_headers::{
+        checksum_from_headers, object_lock_mode_from_str, replication_status_from_str,
+        server_side_encryption_from_str, storage_class_from_str, XAmzChecksum, XAmzObjectLockMode,
+        XAmzReplicationStatus, XAmzServerSideEncryption, XAmzStorageClass,
+    },
+    ApiChecksumType, ApiRestoreStatus, S3RequestBuilder, S3RequestData, S3ResponseData,
+};
 
 pub struct HeadObjectRequest {
     pub key: String,
@@ -30,7 +46,7 @@ impl S3RequestData for HeadObjectRequest {
         endpoint: &str,
     ) -> Result<S3RequestBuilder<Self::ResponseType>> {
         let mut builder = S3RequestBuilder::new(
-            Method::GET,
+            Method::HEAD,
             &self.key,
             access_key,
             secret_key,
@@ -53,13 +69,91 @@ impl S3RequestData for HeadObjectRequest {
     }
 }
 
-pub struct HeadObjectResponse {}
+/// The object metadata parsed from the response headers
+pub struct HeadObjectResponse {
+    pub content_length: Option<u64>,
+    pub content_type: Option<String>,
+    pub content_encoding: Option<String>,
+    pub content_disposition: Option<String>,
+    pub content_language: Option<String>,
+    pub cache_control: Option<String>,
+    pub etag: Option<String>,
+    pub last_modified: Option<DateTime<Utc>>,
+    pub version_id: Option<String>,
+    pub delete_marker: bool,
+    /// STANDARD objects don't return a storage class
+    pub storage_class: XAmzStorageClass,
+    pub server_side_encryption: Option<XAmzServerSideEncryption>,
+    pub kms_key_id: Option<String>,
+    pub object_lock_mode: Option<XAmzObjectLockMode>,
+    pub object_lock_retain_until_date: Option<DateTime<Utc>>,
+    pub object_lock_legal_hold: bool,
+    pub restore_status: Option<ApiRestoreStatus>,
+    pub replication_status: Option<XAmzReplicationStatus>,
+    /// The number of parts of a multipart object, only returned with
+    /// `part_number` or checksum mode
+    pub parts_count: Option<u32>,
+    /// Only returned if the request enables `x-amz-checksum-mode`
+    pub checksum: Option<XAmzChecksum>,
+    pub checksum_type: Option<ApiChecksumType>,
+    pub expiration: Option<ObjectExpiration>,
+    /// The x-amz-meta-* user metadata without the prefix
+    pub metadata: HashMap<String, String>,
+}
+
+impl HeadObjectResponse {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        Self {
+            content_length: get_header_value(headers, "content-length"),
+            content_type: get_header(headers, "content-type"),
+            content_encoding: get_header(headers, "content-encoding"),
+            content_disposition: get_header(headers, "content-disposition"),
+            content_language: get_header(headers, "content-language"),
+            cache_control: get_header(headers, "cache-control"),
+            etag: get_header(headers, "etag"),
+            last_modified: get_header_date(headers, "last-modified"),
+            version_id: get_header(headers, "x-amz-version-id"),
+            delete_marker: get_header_value(headers, "x-amz-delete-marker").unwrap_or(false),
+            storage_class: storage_class_from_str(
+                get_header(headers, "x-amz-storage-class").unwrap_or("STANDARD".to_string()),
+            ),
+            server_side_encryption: get_header(headers, "x-amz-server-side-encryption")
+                .map(|a| server_side_encryption_from_str(&a)),
+            kms_key_id: get_header(headers, "x-amz-server-side-encryption-aws-kms-key-id"),
+            object_lock_mode: get_header(headers, "x-amz-object-lock-mode")
+                .and_then(|m| object_lock_mode_from_str(&m)),
+            object_lock_retain_until_date: get_header_date(
+                headers,
+                "x-amz-object-lock-retain-until-date",
+            ),
+            object_lock_legal_hold: get_header(headers, "x-amz-object-lock-legal-hold")
+                .is_some_and(|v| v == "ON"),
+            restore_status: get_restore_status(headers),
+            replication_status: get_header(headers, "x-amz-replication-status")
+                .map(|s| replication_status_from_str(&s)),
+            parts_count: get_header_value(headers, "x-amz-mp-parts-count"),
+            checksum: checksum_from_headers(headers),
+            checksum_type: get_header(headers, "x-amz-checksum-type")
+                .and_then(|t| checksum_type_from_str(&t).ok()),
+            expiration: get_expiration(headers),
+            metadata: get_user_metadata(headers),
+        }
+    }
+}
 
 impl S3ResponseData for HeadObjectResponse {
-    async fn parse_body(_response: &mut IncomingBody) -> anyhow::Result<Self>
+    /// Without the response headers all fields are empty
+    async fn parse_body(_response: &mut IncomingBody) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(Self::from_headers(&HeaderMap::new()))
+    }
+
+    async fn parse_response(head: &Parts, _response: &mut IncomingBody) -> Result<Self>
     where
         Self: Sized,
     {
-        Ok(Self {})
+        Ok(Self::from_headers(&head.headers))
     }
 }
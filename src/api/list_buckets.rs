@@ -39,6 +39,7 @@ impl S3RequestData for ListBucketsRequest {
     ) -> Result<S3RequestBuilder<Self::ResponseType>> {
         let mut builder =
             S3RequestBuilder::new(Method::GET, "/", access_key, secret_key, region, endpoint);
+        builder.service_request = true;
 
         if let Some(bucket_region) = &self.bucket_region {
             builder.query("bucket-region", Some(bucket_region));
@@ -0,0 +1,261 @@
+use anyhow::{anyhow, Result};
+use wstd::{
+    http::{body::IncomingBody, Method},
+    io::AsyncRead,
+};
+use xml::reader::{EventReader, XmlEvent};
+
+use super::{
+    parse_xml_bool, parse_xml_optional_string, parse_xml_string, parse_xml_value, url_decode,
+    ApiMultipartUpload, S3RequestBuilder, S3RequestData, S3ResponseData,
+};
+
+/// List the in-progress multipart uploads of the bucket
+#[derive(Default)]
+pub struct ListMultipartUploadsRequest {
+    pub delimiter: Option<char>,
+    pub encoding_type: Option<String>,
+    pub key_marker: Option<String>,
+    pub max_uploads: Option<i32>,
+    pub prefix: Option<String>,
+    pub upload_id_marker: Option<String>,
+}
+
+impl S3RequestData for ListMultipartUploadsRequest {
+    type ResponseType = ListMultipartUploadsResponse;
+
+    fn into_builder(
+        &self,
+        access_key: &str,
+        secret_key: &str,
+        region: &str,
+        endpoint: &str,
+    ) -> Result<S3RequestBuilder<Self::ResponseType>> {
+        let mut builder =
+            S3RequestBuilder::new(Method::GET, "/", access_key, secret_key, region, endpoint);
+        builder.query("uploads", None);
+
+        if let Some(delimiter) = &self.delimiter {
+            builder.query("delimiter", Some(&delimiter.to_string()));
+        }
+        if let Some(encoding_type) = &self.encoding_type {
+            builder.query("encoding-type", Some(encoding_type));
+        }
+        if let Some(key_marker) = &self.key_marker {
+            builder.query("key-marker", Some(key_marker));
+        }
+        if let Some(max_uploads) = self.max_uploads {
+            if (1..=1000).contains(&max_uploads) {
+                builder.query("max-uploads", Some(&max_uploads.to_string()));
+            } else {
+                return Err(anyhow!("max_uploads has to be constrained to max_uploads >= 1 and max_uploads <= 1000, max_uploads is {max_uploads}"));
+            }
+        }
+        if let Some(prefix) = &self.prefix {
+            builder.query("prefix", Some(prefix));
+        }
+        if let Some(upload_id_marker) = &self.upload_id_marker {
+            builder.query("upload-id-marker", Some(upload_id_marker));
+        }
+
+        Ok(builder)
+    }
+}
+
+pub struct ListMultipartUploadsResponse {
+    pub bucket: String,
+    pub key_marker: Option<String>,
+    pub upload_id_marker: Option<String>,
+    pub next_key_marker: Option<String>,
+    pub next_upload_id_marker: Option<String>,
+    pub prefix: Option<String>,
+    pub delimiter: Option<String>,
+    pub encoding_type: Option<String>,
+    pub max_uploads: i32,
+    pub is_truncated: bool,
+    pub uploads: Vec<ApiMultipartUpload>,
+    pub common_prefixes: Vec<String>,
+}
+
+impl ListMultipartUploadsResponse {
+    /// Create the request for the next page of uploads
+    ///
+    /// returns None if the response isn't truncated
+    pub fn next_page(&self) -> Option<ListMultipartUploadsRequest> {
+        if !self.is_truncated {
+            return None;
+        }
+
+        // With encoding-type=url the values are encoded and would be encoded
+        // again in the query string
+        let is_url_encoded = self
+            .encoding_type
+            .as_ref()
+            .is_some_and(|e| e.eq_ignore_ascii_case("url"));
+        let decode = |value: &Option<String>| match is_url_encoded {
+            true => value.as_deref().map(url_decode),
+            false => value.clone(),
+        };
+
+        Some(ListMultipartUploadsRequest {
+            delimiter: decode(&self.delimiter).and_then(|d| d.chars().next()),
+            encoding_type: self.encoding_type.clone(),
+            key_marker: decode(&self.next_key_marker),
+            max_uploads: Some(self.max_uploads),
+            prefix: decode(&self.prefix),
+            upload_id_marker: self.next_upload_id_marker.clone(),
+        })
+    }
+}
+
+impl S3ResponseData for ListMultipartUploadsResponse {
+    async fn parse_body(response: &mut IncomingBody) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut data = Vec::<u8>::new();
+        response.read_to_end(&mut data).await?;
+        let mut parser = EventReader::new(data.as_slice());
+
+        let mut list_uploads_response = ListMultipartUploadsResponse {
+            bucket: String::new(),
+            key_marker: None,
+            upload_id_marker: None,
+            next_key_marker: None,
+            next_upload_id_marker: None,
+            prefix: None,
+            delimiter: None,
+            encoding_type: None,
+            max_uploads: 0,
+            is_truncated: false,
+            uploads: Vec::new(),
+            common_prefixes: Vec::new(),
+        };
+        loop {
+            match parser.next()? {
+                XmlEvent::EndDocument => break,
+
+                XmlEvent::StartElement { name, .. } if name.local_name == "Bucket" => {
+                    list_uploads_response.bucket = parse_xml_string(&mut parser, "Bucket")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "KeyMarker" => {
+                    list_uploads_response.key_marker =
+                        parse_xml_optional_string(&mut parser, "KeyMarker")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "UploadIdMarker" => {
+                    list_uploads_response.upload_id_marker =
+                        parse_xml_optional_string(&mut parser, "UploadIdMarker")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "NextKeyMarker" => {
+                    list_uploads_response.next_key_marker =
+                        parse_xml_optional_string(&mut parser, "NextKeyMarker")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "NextUploadIdMarker" => {
+                    list_uploads_response.next_upload_id_marker =
+                        parse_xml_optional_string(&mut parser, "NextUploadIdMarker")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "Prefix" => {
+                    list_uploads_response.prefix =
+                        parse_xml_optional_string(&mut parser, "Prefix")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "Delimiter" => {
+                    list_uploads_response.delimiter =
+                        parse_xml_optional_string(&mut parser, "Delimiter")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "EncodingType" => {
+                    list_uploads_response.encoding_type =
+                        parse_xml_optional_string(&mut parser, "EncodingType")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "MaxUploads" => {
+                    list_uploads_response.max_uploads =
+                        parse_xml_value::<i32>(&mut parser, "MaxUploads")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "IsTruncated" => {
+                    list_uploads_response.is_truncated =
+                        parse_xml_bool(&mut parser, "IsTruncated")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "Upload" => {
+                    list_uploads_response
+                        .uploads
+                        .push(ApiMultipartUpload::parse(&mut parser)?);
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "CommonPrefixes" => {
+                    loop {
+                        match parser.next()? {
+                            XmlEvent::EndElement { name }
+                                if name.local_name == "CommonPrefixes" =>
+                            {
+                                break
+                            }
+
+                            XmlEvent::StartElement { name, .. } if name.local_name == "Prefix" => {
+                                list_uploads_response
+                                    .common_prefixes
+                                    .push(parse_xml_string(&mut parser, "CommonPrefixes.Prefix")?);
+                            }
+
+                            _ => {}
+                        }
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        Ok(list_uploads_response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_response(encoding_type: Option<&str>) -> ListMultipartUploadsResponse {
+        ListMultipartUploadsResponse {
+            bucket: "mybucket".to_string(),
+            key_marker: None,
+            upload_id_marker: None,
+            next_key_marker: Some("photos/my+photo%2B%C3%A4.jpg".to_string()),
+            next_upload_id_marker: Some("upload-id".to_string()),
+            prefix: Some("photos%2F".to_string()),
+            delimiter: Some("%2F".to_string()),
+            encoding_type: encoding_type.map(|e| e.to_string()),
+            max_uploads: 100,
+            is_truncated: true,
+            uploads: Vec::new(),
+            common_prefixes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn next_page_decodes_url_encoded_values() {
+        let request = get_response(Some("url")).next_page().unwrap();
+
+        assert_eq!(request.key_marker.as_deref(), Some("photos/my photo+ä.jpg"));
+        assert_eq!(request.prefix.as_deref(), Some("photos/"));
+        assert_eq!(request.delimiter, Some('/'));
+        assert_eq!(request.upload_id_marker.as_deref(), Some("upload-id"));
+        assert_eq!(request.encoding_type.as_deref(), Some("url"));
+        assert_eq!(request.max_uploads, Some(100));
+    }
+
+    #[test]
+    fn next_page_keeps_values_without_encoding() {
+        let request = get_response(None).next_page().unwrap();
+
+        assert_eq!(
+            request.key_marker.as_deref(),
+            Some("photos/my+photo%2B%C3%A4.jpg")
+        );
+        assert_eq!(request.prefix.as_deref(), Some("photos%2F"));
+    }
+
+    #[test]
+    fn no_next_page() {
+        let mut response = get_response(Some("url"));
+        response.is_truncated = false;
+
+        assert!(response.next_page().is_none());
+    }
+}
@@ -0,0 +1,195 @@
+use anyhow::{anyhow, Result};
+use wstd::{
+    http::{body::IncomingBody, Method},
+    io::AsyncRead,
+};
+use xml::reader::{EventReader, XmlEvent};
+
+use super::{
+    checksum_algorithm_from_str, checksum_type_from_str, parse_xml_bool, parse_xml_optional_string,
+    parse_xml_string, parse_xml_value,
+    x_amz_headers::{storage_class_from_str, XAmzStorageClass},
+    ApiChecksumType, ApiOwner, ApiPart, ChecksumAlgorithm, S3RequestBuilder, S3RequestData,
+    S3ResponseData,
+};
+
+/// List the parts uploaded for a multipart upload
+pub struct ListPartsRequest {
+    pub key: String,
+    pub upload_id: String,
+    pub max_parts: Option<i32>,
+    pub part_number_marker: Option<u32>,
+}
+
+impl ListPartsRequest {
+    pub fn new(key: &str, upload_id: &str) -> Self {
+        Self {
+            key: key.to_owned(),
+            upload_id: upload_id.to_owned(),
+            max_parts: None,
+            part_number_marker: None,
+        }
+    }
+}
+
+impl S3RequestData for ListPartsRequest {
+    type ResponseType = ListPartsResponse;
+
+    fn into_builder(
+        &self,
+        access_key: &str,
+        secret_key: &str,
+        region: &str,
+        endpoint: &str,
+    ) -> Result<S3RequestBuilder<Self::ResponseType>> {
+        let mut builder = S3RequestBuilder::new(
+            Method::GET,
+            &self.key,
+            access_key,
+            secret_key,
+            region,
+            endpoint,
+        );
+        builder.query("uploadId", Some(&self.upload_id));
+
+        if let Some(max_parts) = self.max_parts {
+            if (1..=1000).contains(&max_parts) {
+                builder.query("max-parts", Some(&max_parts.to_string()));
+            } else {
+                return Err(anyhow!("max_parts has to be constrained to max_parts >= 1 and max_parts <= 1000, max_parts is {max_parts}"));
+            }
+        }
+        if let Some(marker) = self.part_number_marker {
+            builder.query("part-number-marker", Some(&marker.to_string()));
+        }
+
+        Ok(builder)
+    }
+}
+
+pub struct ListPartsResponse {
+    pub bucket: String,
+    pub key: String,
+    pub upload_id: String,
+    pub part_number_marker: Option<u32>,
+    pub next_part_number_marker: Option<u32>,
+    pub max_parts: i32,
+    pub is_truncated: bool,
+    pub parts: Vec<ApiPart>,
+    pub initiator: Option<ApiOwner>,
+    pub owner: Option<ApiOwner>,
+    pub storage_class: XAmzStorageClass,
+    pub checksum_algorithm: Option<ChecksumAlgorithm>,
+    pub checksum_type: Option<ApiChecksumType>,
+}
+
+impl ListPartsResponse {
+    /// Create the request for the next page of parts
+    ///
+    /// returns None if the response isn't truncated
+    pub fn next_page(&self) -> Option<ListPartsRequest> {
+        if !self.is_truncated {
+            return None;
+        }
+
+        Some(ListPartsRequest {
+            key: self.key.clone(),
+            upload_id: self.upload_id.clone(),
+            max_parts: Some(self.max_parts),
+            part_number_marker: self.next_part_number_marker,
+        })
+    }
+}
+
+impl S3ResponseData for ListPartsResponse {
+    async fn parse_body(response: &mut IncomingBody) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut data = Vec::<u8>::new();
+        response.read_to_end(&mut data).await?;
+        let mut parser = EventReader::new(data.as_slice());
+
+        let mut list_parts_response = ListPartsResponse {
+            bucket: String::new(),
+            key: String::new(),
+            upload_id: String::new(),
+            part_number_marker: None,
+            next_part_number_marker: None,
+            max_parts: 0,
+            is_truncated: false,
+            parts: Vec::new(),
+            initiator: None,
+            owner: None,
+            storage_class: XAmzStorageClass::Standard,
+            checksum_algorithm: None,
+            checksum_type: None,
+        };
+        loop {
+            match parser.next()? {
+                XmlEvent::EndDocument => break,
+
+                XmlEvent::StartElement { name, .. } if name.local_name == "Bucket" => {
+                    list_parts_response.bucket = parse_xml_string(&mut parser, "Bucket")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "Key" => {
+                    list_parts_response.key = parse_xml_string(&mut parser, "Key")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "UploadId" => {
+                    list_parts_response.upload_id = parse_xml_string(&mut parser, "UploadId")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "PartNumberMarker" => {
+                    if let Some(marker) =
+                        parse_xml_optional_string(&mut parser, "PartNumberMarker")?
+                    {
+                        list_parts_response.part_number_marker = Some(marker.parse::<u32>()?);
+                    }
+                }
+                XmlEvent::StartElement { name, .. }
+                    if name.local_name == "NextPartNumberMarker" =>
+                {
+                    if let Some(marker) =
+                        parse_xml_optional_string(&mut parser, "NextPartNumberMarker")?
+                    {
+                        list_parts_response.next_part_number_marker = Some(marker.parse::<u32>()?);
+                    }
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "MaxParts" => {
+                    list_parts_response.max_parts =
+                        parse_xml_value::<i32>(&mut parser, "MaxParts")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "IsTruncated" => {
+                    list_parts_response.is_truncated = parse_xml_bool(&mut parser, "IsTruncated")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "Part" => {
+                    list_parts_response.parts.push(ApiPart::parse(&mut parser)?);
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "Initiator" => {
+                    list_parts_response.initiator =
+                        Some(ApiOwner::parse_element(&mut parser, "Initiator")?);
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "Owner" => {
+                    list_parts_response.owner = Some(ApiOwner::parse(&mut parser)?);
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "StorageClass" => {
+                    list_parts_response.storage_class =
+                        storage_class_from_str(parse_xml_string(&mut parser, "StorageClass")?);
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "ChecksumAlgorithm" => {
+                    list_parts_response.checksum_algorithm = Some(checksum_algorithm_from_str(
+                        parse_xml_string(&mut parser, "ChecksumAlgorithm")?,
+                    ));
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "ChecksumType" => {
+                    list_parts_response.checksum_type = Some(checksum_type_from_str(
+                        &parse_xml_string(&mut parser, "ChecksumType")?,
+                    )?);
+                }
+
+                _ => {}
+            }
+        }
+
+        Ok(list_parts_response)
+    }
+}
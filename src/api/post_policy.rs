@@ -0,0 +1,253 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use chrono::{DateTime, Utc};
+use serde_json::{json, Map, Value};
+use wstd::http::Scheme;
+
+use super::{
+    get_scheme_host, get_signature_key, hmac_sha256, AWS_SIGN_ALGORITHM, PRESIGN_MAX_EXPIRES,
+};
+use crate::AWS_SERVICE;
+
+pub enum PostPolicyMatch {
+    Exact(String),
+    StartsWith(String),
+}
+
+/// A browser based upload with an HTML form
+///
+/// [PostPolicy::build] creates the url to post the form to and the form
+/// fields that have to be included before the `file` field.
+pub struct PostPolicyForm {
+    pub url: String,
+    pub fields: Vec<(String, String)>,
+}
+
+/// Build and sign a POST policy for browser based uploads
+///
+/// # Examples
+/// ```no_run
+/// # use s3_wasi_http::api::post_policy::PostPolicy;
+/// # fn example() -> anyhow::Result<()> {
+/// use std::time::Duration;
+///
+/// let form = PostPolicy::new(Duration::from_secs(10 * 60))
+///     .bucket("mybucket")
+///     .key_starts_with("uploads/")
+///     .content_length_range(1, 10 * 1024 * 1024)
+///     .content_type("image/png")
+///     .build("access_key", "secret_key", None, "eu-north-1", "mybucket.s3.eu-north-1.amazonaws.com")?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct PostPolicy {
+    expires_in: Duration,
+    bucket: Option<String>,
+    key: Option<PostPolicyMatch>,
+    content_type: Option<PostPolicyMatch>,
+    content_length_range: Option<(u64, u64)>,
+    success_action_status: Option<u16>,
+    metadata: Vec<(String, String)>,
+}
+
+impl PostPolicy {
+    /// Create a new policy, `expires_in` has to be between 1 second and 7 days.
+    pub fn new(expires_in: Duration) -> Self {
+        Self {
+            expires_in,
+            bucket: None,
+            key: None,
+            content_type: None,
+            content_length_range: None,
+            success_action_status: None,
+            metadata: Vec::new(),
+        }
+    }
+
+    pub fn bucket(self, bucket: &str) -> Self {
+        Self {
+            bucket: Some(bucket.to_owned()),
+            ..self
+        }
+    }
+    pub fn key(self, key: &str) -> Self {
+        Self {
+            key: Some(PostPolicyMatch::Exact(key.to_owned())),
+            ..self
+        }
+    }
+    /// Allow any key starting with `prefix`
+    ///
+    /// The `key` form field is set to `{prefix}${filename}`, replace it to
+    /// control the key from the form.
+    pub fn key_starts_with(self, prefix: &str) -> Self {
+        Self {
+            key: Some(PostPolicyMatch::StartsWith(prefix.to_owned())),
+            ..self
+        }
+    }
+    pub fn content_type(self, content_type: &str) -> Self {
+        Self {
+            content_type: Some(PostPolicyMatch::Exact(content_type.to_owned())),
+            ..self
+        }
+    }
+    /// Allow any content type starting with `prefix`, e.g. `image/`
+    ///
+    /// The `Content-Type` form field has to be added to the form.
+    pub fn content_type_starts_with(self, prefix: &str) -> Self {
+        Self {
+            content_type: Some(PostPolicyMatch::StartsWith(prefix.to_owned())),
+            ..self
+        }
+    }
+    /// The minimum and maximum allowed size of the upload in bytes
+    pub fn content_length_range(self, min: u64, max: u64) -> Self {
+        Self {
+            content_length_range: Some((min, max)),
+            ..self
+        }
+    }
+    /// The status code returned on a successful upload, 200, 201 or 204
+    pub fn success_action_status(self, status: u16) -> Self {
+        Self {
+            success_action_status: Some(status),
+            ..self
+        }
+    }
+    /// Add x-amz-meta-{key} user metadata to the uploaded object
+    pub fn add_metadata(self, key: &str, value: &str) -> Self {
+        let mut metadata = self.metadata;
+        metadata.push((
+            format!("x-amz-meta-{}", key.to_lowercase()),
+            value.to_owned(),
+        ));
+        Self { metadata, ..self }
+    }
+
+    fn get_policy(&self, expiration: DateTime<Utc>, fields: &[(String, String)]) -> Result<String> {
+        let mut conditions = Vec::new();
+        if let Some(bucket) = &self.bucket {
+            conditions.push(json!({ "bucket": bucket }));
+        }
+        match &self.key {
+            Some(PostPolicyMatch::Exact(key)) => conditions.push(json!({ "key": key })),
+            Some(PostPolicyMatch::StartsWith(prefix)) => {
+                conditions.push(json!(["starts-with", "$key", prefix]));
+            }
+            None => {}
+        }
+        match &self.content_type {
+            Some(PostPolicyMatch::Exact(content_type)) => {
+                conditions.push(json!({ "Content-Type": content_type }));
+            }
+            Some(PostPolicyMatch::StartsWith(prefix)) => {
+                conditions.push(json!(["starts-with", "$Content-Type", prefix]));
+            }
+            None => {}
+        }
+        if let Some((min, max)) = self.content_length_range {
+            if min > max {
+                return Err(anyhow!(
+                    "content_length_range min has to be <= max, range is {min}-{max}"
+                ));
+            }
+            conditions.push(json!(["content-length-range", min, max]));
+        }
+        for (key, value) in fields {
+            // Covered by the conditions above
+            if key == "key" || key == "Content-Type" {
+                continue;
+            }
+            let mut condition = Map::new();
+            condition.insert(key.to_owned(), Value::from(value.as_str()));
+            conditions.push(Value::Object(condition));
+        }
+
+        let policy = json!({
+            "expiration": expiration.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+            "conditions": conditions,
+        });
+
+        Ok(serde_json::to_string(&policy)?)
+    }
+
+    /// Sign the policy and create the form fields
+    ///
+    /// `session_token` is required for temporary credentials
+    pub fn build(
+        &self,
+        access_key: &str,
+        secret_key: &str,
+        session_token: Option<&str>,
+        region: &str,
+        endpoint: &str,
+    ) -> Result<PostPolicyForm> {
+        let expires = self.expires_in.as_secs();
+        if !(1..=PRESIGN_MAX_EXPIRES).contains(&expires) {
+            return Err(anyhow!("expires_in has to be constrained to expires_in >= 1 and expires_in <= {PRESIGN_MAX_EXPIRES} seconds, expires_in is {expires}"));
+        }
+        if let Some(status) = self.success_action_status {
+            if !matches!(status, 200 | 201 | 204) {
+                return Err(anyhow!(
+                    "success_action_status has to be 200, 201 or 204, success_action_status is {status}"
+                ));
+            }
+        }
+
+        // Get current time in AWS format
+        let now = Utc::now();
+        let date_stamp = now.format("%Y%m%d").to_string();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let credential_scope = format!("{date_stamp}/{region}/{AWS_SERVICE}/aws4_request");
+
+        let mut fields = Vec::new();
+        match &self.key {
+            Some(PostPolicyMatch::Exact(key)) => {
+                fields.push(("key".to_string(), key.to_owned()));
+            }
+            Some(PostPolicyMatch::StartsWith(prefix)) => {
+                fields.push(("key".to_string(), format!("{prefix}${{filename}}")));
+            }
+            None => {}
+        }
+        if let Some(PostPolicyMatch::Exact(content_type)) = &self.content_type {
+            fields.push(("Content-Type".to_string(), content_type.to_owned()));
+        }
+        if let Some(status) = self.success_action_status {
+            fields.push(("success_action_status".to_string(), status.to_string()));
+        }
+        for (key, value) in &self.metadata {
+            fields.push((key.to_owned(), value.to_owned()));
+        }
+        fields.push((
+            "x-amz-algorithm".to_string(),
+            AWS_SIGN_ALGORITHM.to_string(),
+        ));
+        fields.push((
+            "x-amz-credential".to_string(),
+            format!("{access_key}/{credential_scope}"),
+        ));
+        fields.push(("x-amz-date".to_string(), amz_date));
+        if let Some(session_token) = session_token {
+            fields.push(("x-amz-security-token".to_string(), session_token.to_owned()));
+        }
+
+        // The base64 encoded policy is the string to sign
+        let policy = BASE64_STANDARD.encode(self.get_policy(now + self.expires_in, &fields)?);
+        let signing_key = get_signature_key(secret_key, &date_stamp, region, AWS_SERVICE)?;
+        let signature = hex::encode(hmac_sha256(&signing_key, policy.as_bytes())?);
+
+        fields.push(("policy".to_string(), policy));
+        fields.push(("x-amz-signature".to_string(), signature));
+
+        let (scheme, host) = get_scheme_host(endpoint, &Scheme::HTTPS)?;
+
+        Ok(PostPolicyForm {
+            url: format!("{scheme}://{host}/"),
+            fields,
+        })
+    }
+}
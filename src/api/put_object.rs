@@ -1,11 +1,35 @@
+use std::collections::HashMap;
+
 use anyhow::Result;
-use wstd::http::{body::IncomingBody, Method};
+use http::response::Parts;
+use wstd::http::{body::IncomingBody, HeaderMap, Method};
 
-use super::{S3RequestBuilder, S3RequestData, S3ResponseData};
+use super::{
+    checksum_type_from_str,
+    response_headers::{get_expiration, get_header, get_header_value, ObjectExpiration},
+    user_metadata::get_metadata_headers,
+    x_amz_headers::{
+        checksum_from_headers, server_side_encryption_from_str, XAmzChecksum,
+        XAmzServerSideEncryption,
+    },
+    ApiChecksumType, S3RequestBuilder, S3RequestData, S3ResponseData,
+};
 
 pub struct PutObjectRequest {
     pub key: String,
     pub body: Vec<u8>,
+    /// x-amz-meta-* user metadata, keys without the prefix
+    pub metadata: HashMap<String, String>,
+}
+
+impl PutObjectRequest {
+    pub fn new(key: &str, body: Vec<u8>) -> Self {
+        Self {
+            key: key.to_owned(),
+            body,
+            metadata: HashMap::new(),
+        }
+    }
 }
 
 impl S3RequestData for PutObjectRequest {
@@ -27,18 +51,69 @@ impl S3RequestData for PutObjectRequest {
             endpoint,
         );
         builder.body(&self.body);
+        builder.headers(get_metadata_headers(&self.metadata)?);
 
         Ok(builder)
     }
 }
 
-pub struct PutObjectResponse {}
+/// The stored object parsed from the response headers
+pub struct PutObjectResponse {
+    pub etag: Option<String>,
+    /// Only returned if versioning is enabled on the bucket
+    pub version_id: Option<String>,
+    pub checksum: Option<XAmzChecksum>,
+    pub checksum_type: Option<ApiChecksumType>,
+    pub server_side_encryption: Option<XAmzServerSideEncryption>,
+    pub kms_key_id: Option<String>,
+    pub bucket_key_enabled: bool,
+    pub sse_customer_algorithm: Option<String>,
+    pub sse_customer_key_md5: Option<String>,
+    pub expiration: Option<ObjectExpiration>,
+}
+
+impl PutObjectResponse {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        Self {
+            etag: get_header(headers, "etag"),
+            version_id: get_header(headers, "x-amz-version-id"),
+            checksum: checksum_from_headers(headers),
+            checksum_type: get_header(headers, "x-amz-checksum-type")
+                .and_then(|t| checksum_type_from_str(&t).ok()),
+            server_side_encryption: get_header(headers, "x-amz-server-side-encryption")
+                .map(|a| server_side_encryption_from_str(&a)),
+            kms_key_id: get_header(headers, "x-amz-server-side-encryption-aws-kms-key-id"),
+            bucket_key_enabled: get_header_value(
+                headers,
+                "x-amz-server-side-encryption-bucket-key-enabled",
+            )
+            .unwrap_or(false),
+            sse_customer_algorithm: get_header(
+                headers,
+                "x-amz-server-side-encryption-customer-algorithm",
+            ),
+            sse_customer_key_md5: get_header(
+                headers,
+                "x-amz-server-side-encryption-customer-key-md5",
+            ),
+            expiration: get_expiration(headers),
+        }
+    }
+}
 
 impl S3ResponseData for PutObjectResponse {
+    /// Without the response headers all fields are empty
     async fn parse_body(_response: &mut IncomingBody) -> Result<Self>
     where
         Self: Sized,
     {
-        Ok(Self {})
+        Ok(Self::from_headers(&HeaderMap::new()))
+    }
+
+    async fn parse_response(head: &Parts, _response: &mut IncomingBody) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(Self::from_headers(&head.headers))
     }
 }
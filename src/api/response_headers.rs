@@ -0,0 +1,102 @@
+use std::{collections::HashMap, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use wstd::http::HeaderMap;
+
+use super::{
+    user_metadata::{decode_metadata_value, USER_METADATA_PREFIX},
+    ApiRestoreStatus,
+};
+
+/// The lifecycle expiration of an object from the x-amz-expiration header
+pub struct ObjectExpiration {
+    pub expiry_date: DateTime<Utc>,
+    pub rule_id: String,
+}
+
+/// Get a header as a string, `None` if it's missing or not valid ascii
+pub(crate) fn get_header(headers: &HeaderMap, key: &str) -> Option<String> {
+    headers
+        .get(key)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_owned())
+}
+
+/// Get a header parsed into `T`, `None` if it's missing or invalid
+pub(crate) fn get_header_value<T: FromStr>(headers: &HeaderMap, key: &str) -> Option<T> {
+    headers
+        .get(key)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+}
+
+/// Get an http date (`Fri, 21 Dec 2012 00:00:00 GMT`) or ISO 8601 header
+pub(crate) fn get_header_date(headers: &HeaderMap, key: &str) -> Option<DateTime<Utc>> {
+    parse_date(&get_header(headers, key)?)
+}
+
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    // The day of the week is optional and chrono rejects a wrong one
+    let without_weekday = value.split_once(", ").map_or(value, |(_, date)| date);
+    DateTime::parse_from_rfc2822(without_weekday)
+        .or_else(|_| DateTime::parse_from_rfc3339(value))
+        .ok()
+        .map(|date| date.to_utc())
+}
+
+/// Parse the `key="value", key2="value2"` pairs of the x-amz-restore and
+/// x-amz-expiration headers, values can contain commas
+fn parse_key_values(value: &str) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    let mut rest = value;
+    while let Some((key, value)) = rest.split_once("=\"") {
+        let Some((value, next)) = value.split_once('"') else {
+            break;
+        };
+        values.insert(
+            key.trim_matches(|c: char| c == ',' || c.is_whitespace())
+                .to_owned(),
+            value.to_owned(),
+        );
+        rest = next;
+    }
+
+    values
+}
+
+/// Parse the x-amz-expiration header
+pub(crate) fn get_expiration(headers: &HeaderMap) -> Option<ObjectExpiration> {
+    let values = parse_key_values(&get_header(headers, "x-amz-expiration")?);
+    Some(ObjectExpiration {
+        expiry_date: parse_date(values.get("expiry-date")?)?,
+        rule_id: values.get("rule-id").cloned().unwrap_or_default(),
+    })
+}
+
+/// Parse the x-amz-restore header
+///
+/// Like the ListObjectsV2 restore status the expiry date is the current time
+/// while the restore is in progress.
+pub(crate) fn get_restore_status(headers: &HeaderMap) -> Option<ApiRestoreStatus> {
+    let values = parse_key_values(&get_header(headers, "x-amz-restore")?);
+    Some(ApiRestoreStatus {
+        is_restore_in_progress: values.get("ongoing-request")? == "true",
+        restore_expiry_date: values
+            .get("expiry-date")
+            .and_then(|date| parse_date(date))
+            .unwrap_or_else(Utc::now),
+    })
+}
+
+/// Get the x-amz-meta-* user metadata, keys are returned without the prefix
+/// and RFC 2047 encoded values are decoded
+pub(crate) fn get_user_metadata(headers: &HeaderMap) -> HashMap<String, String> {
+    headers
+        .iter()
+        .filter_map(|(key, value)| {
+            let key = key.as_str().strip_prefix(USER_METADATA_PREFIX)?;
+            let value = decode_metadata_value(&String::from_utf8_lossy(value.as_bytes()));
+            Some((key.to_owned(), value))
+        })
+        .collect()
+}
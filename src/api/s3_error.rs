@@ -0,0 +1,469 @@
+use std::fmt;
+
+use anyhow::Result;
+use http::{response::Parts, StatusCode};
+use wstd::{http::body::IncomingBody, io::AsyncRead};
+use xml::reader::{EventReader, XmlEvent};
+
+use super::{parse_xml_optional_string, parse_xml_string};
+
+/// Well-known S3 error codes
+///
+/// See <https://docs.aws.amazon.com/AmazonS3/latest/API/ErrorResponses.html>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum S3ErrorCode {
+    AccessDenied,
+    AuthorizationHeaderMalformed,
+    BadDigest,
+    BucketAlreadyExists,
+    BucketAlreadyOwnedByYou,
+    BucketNotEmpty,
+    EntityTooLarge,
+    EntityTooSmall,
+    ExpiredToken,
+    InternalError,
+    InvalidAccessKeyId,
+    InvalidArgument,
+    InvalidBucketName,
+    InvalidDigest,
+    InvalidObjectState,
+    InvalidPart,
+    InvalidPartOrder,
+    InvalidRange,
+    InvalidRequest,
+    InvalidToken,
+    MalformedXML,
+    MethodNotAllowed,
+    MissingContentLength,
+    NoSuchBucket,
+    NoSuchKey,
+    NoSuchUpload,
+    NoSuchVersion,
+    /// A response without an error body, e.g. HeadObject for a missing key
+    NotFound,
+    NotImplemented,
+    NotModified,
+    PermanentRedirect,
+    PreconditionFailed,
+    RequestTimeTooSkewed,
+    RequestTimeout,
+    ServiceUnavailable,
+    SignatureDoesNotMatch,
+    SlowDown,
+    TemporaryRedirect,
+    TooManyBuckets,
+    XAmzContentSHA256Mismatch,
+    Unknown(String),
+}
+
+pub(crate) fn s3_error_code_from_str(code: &str) -> S3ErrorCode {
+    match code {
+        "AccessDenied" => S3ErrorCode::AccessDenied,
+        "AuthorizationHeaderMalformed" => S3ErrorCode::AuthorizationHeaderMalformed,
+        "BadDigest" => S3ErrorCode::BadDigest,
+        "BucketAlreadyExists" => S3ErrorCode::BucketAlreadyExists,
+        "BucketAlreadyOwnedByYou" => S3ErrorCode::BucketAlreadyOwnedByYou,
+        "BucketNotEmpty" => S3ErrorCode::BucketNotEmpty,
+        "EntityTooLarge" => S3ErrorCode::EntityTooLarge,
+        "EntityTooSmall" => S3ErrorCode::EntityTooSmall,
+        "ExpiredToken" => S3ErrorCode::ExpiredToken,
+        "InternalError" => S3ErrorCode::InternalError,
+        "InvalidAccessKeyId" => S3ErrorCode::InvalidAccessKeyId,
+        "InvalidArgument" => S3ErrorCode::InvalidArgument,
+        "InvalidBucketName" => S3ErrorCode::InvalidBucketName,
+        "InvalidDigest" => S3ErrorCode::InvalidDigest,
+        "InvalidObjectState" => S3ErrorCode::InvalidObjectState,
+        "InvalidPart" => S3ErrorCode::InvalidPart,
+        "InvalidPartOrder" => S3ErrorCode::InvalidPartOrder,
+        "InvalidRange" => S3ErrorCode::InvalidRange,
+        "InvalidRequest" => S3ErrorCode::InvalidRequest,
+        "InvalidToken" => S3ErrorCode::InvalidToken,
+        "MalformedXML" => S3ErrorCode::MalformedXML,
+        "MethodNotAllowed" => S3ErrorCode::MethodNotAllowed,
+        "MissingContentLength" => S3ErrorCode::MissingContentLength,
+        "NoSuchBucket" => S3ErrorCode::NoSuchBucket,
+        "NoSuchKey" => S3ErrorCode::NoSuchKey,
+        "NoSuchUpload" => S3ErrorCode::NoSuchUpload,
+        "NoSuchVersion" => S3ErrorCode::NoSuchVersion,
+        "NotFound" => S3ErrorCode::NotFound,
+        "NotImplemented" => S3ErrorCode::NotImplemented,
+        "NotModified" => S3ErrorCode::NotModified,
+        "PermanentRedirect" => S3ErrorCode::PermanentRedirect,
+        "PreconditionFailed" => S3ErrorCode::PreconditionFailed,
+        "RequestTimeTooSkewed" => S3ErrorCode::RequestTimeTooSkewed,
+        "RequestTimeout" => S3ErrorCode::RequestTimeout,
+        "ServiceUnavailable" => S3ErrorCode::ServiceUnavailable,
+        "SignatureDoesNotMatch" => S3ErrorCode::SignatureDoesNotMatch,
+        "SlowDown" => S3ErrorCode::SlowDown,
+        "TemporaryRedirect" => S3ErrorCode::TemporaryRedirect,
+        "TooManyBuckets" => S3ErrorCode::TooManyBuckets,
+        "XAmzContentSHA256Mismatch" => S3ErrorCode::XAmzContentSHA256Mismatch,
+        c => S3ErrorCode::Unknown(c.to_string()),
+    }
+}
+
+pub(crate) fn s3_error_code_to_str(code: &S3ErrorCode) -> &str {
+    match code {
+        S3ErrorCode::AccessDenied => "AccessDenied",
+        S3ErrorCode::AuthorizationHeaderMalformed => "AuthorizationHeaderMalformed",
+        S3ErrorCode::BadDigest => "BadDigest",
+        S3ErrorCode::BucketAlreadyExists => "BucketAlreadyExists",
+        S3ErrorCode::BucketAlreadyOwnedByYou => "BucketAlreadyOwnedByYou",
+        S3ErrorCode::BucketNotEmpty => "BucketNotEmpty",
+        S3ErrorCode::EntityTooLarge => "EntityTooLarge",
+        S3ErrorCode::EntityTooSmall => "EntityTooSmall",
+        S3ErrorCode::ExpiredToken => "ExpiredToken",
+        S3ErrorCode::InternalError => "InternalError",
+        S3ErrorCode::InvalidAccessKeyId => "InvalidAccessKeyId",
+        S3ErrorCode::InvalidArgument => "InvalidArgument",
+        S3ErrorCode::InvalidBucketName => "InvalidBucketName",
+        S3ErrorCode::InvalidDigest => "InvalidDigest",
+        S3ErrorCode::InvalidObjectState => "InvalidObjectState",
+        S3ErrorCode::InvalidPart => "InvalidPart",
+        S3ErrorCode::InvalidPartOrder => "InvalidPartOrder",
+        S3ErrorCode::InvalidRange => "InvalidRange",
+        S3ErrorCode::InvalidRequest => "InvalidRequest",
+        S3ErrorCode::InvalidToken => "InvalidToken",
+        S3ErrorCode::MalformedXML => "MalformedXML",
+        S3ErrorCode::MethodNotAllowed => "MethodNotAllowed",
+        S3ErrorCode::MissingContentLength => "MissingContentLength",
+        S3ErrorCode::NoSuchBucket => "NoSuchBucket",
+        S3ErrorCode::NoSuchKey => "NoSuchKey",
+        S3ErrorCode::NoSuchUpload => "NoSuchUpload",
+        S3ErrorCode::NoSuchVersion => "NoSuchVersion",
+        S3ErrorCode::NotFound => "NotFound",
+        S3ErrorCode::NotImplemented => "NotImplemented",
+        S3ErrorCode::NotModified => "NotModified",
+        S3ErrorCode::PermanentRedirect => "PermanentRedirect",
+        S3ErrorCode::PreconditionFailed => "PreconditionFailed",
+        S3ErrorCode::RequestTimeTooSkewed => "RequestTimeTooSkewed",
+        S3ErrorCode::RequestTimeout => "RequestTimeout",
+        S3ErrorCode::ServiceUnavailable => "ServiceUnavailable",
+        S3ErrorCode::SignatureDoesNotMatch => "SignatureDoesNotMatch",
+        S3ErrorCode::SlowDown => "SlowDown",
+        S3ErrorCode::TemporaryRedirect => "TemporaryRedirect",
+        S3ErrorCode::TooManyBuckets => "TooManyBuckets",
+        S3ErrorCode::XAmzContentSHA256Mismatch => "XAmzContentSHA256Mismatch",
+        S3ErrorCode::Unknown(c) => c,
+    }
+}
+
+/// The error code of a response without an error body
+fn s3_error_code_from_status(status: StatusCode) -> S3ErrorCode {
+    match status {
+        StatusCode::NOT_MODIFIED => S3ErrorCode::NotModified,
+        StatusCode::FORBIDDEN => S3ErrorCode::AccessDenied,
+        StatusCode::NOT_FOUND => S3ErrorCode::NotFound,
+        StatusCode::PRECONDITION_FAILED => S3ErrorCode::PreconditionFailed,
+        StatusCode::RANGE_NOT_SATISFIABLE => S3ErrorCode::InvalidRange,
+        StatusCode::INTERNAL_SERVER_ERROR => S3ErrorCode::InternalError,
+        StatusCode::SERVICE_UNAVAILABLE => S3ErrorCode::ServiceUnavailable,
+        s => S3ErrorCode::Unknown(s.as_str().to_string()),
+    }
+}
+
+impl fmt::Display for S3ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(s3_error_code_to_str(self))
+    }
+}
+
+/// An error response from S3
+///
+/// Returned by [super::S3Response::into_response_data] for non-2xx responses
+/// and 200 responses with an error body, use [anyhow::Error::downcast_ref]
+/// to get it from the error.
+///
+/// # Examples
+/// ```no_run
+/// # use s3_wasi_http::{S3Client, api::{get_object::GetObjectRequest, s3_error::{S3Error, S3ErrorCode}}};
+/// # async fn example() -> anyhow::Result<()> {
+/// let client = S3Client::new_from_env();
+///
+/// let mut resp = client.get_object(GetObjectRequest::from_key("myobject")).await?;
+/// match resp.into_response_data().await {
+///     Ok(object) => {}
+///     Err(e) => match e.downcast_ref::<S3Error>() {
+///         Some(error) if error.code == S3ErrorCode::NoSuchKey => {}
+///         _ => return Err(e),
+///     },
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct S3Error {
+    pub status: StatusCode,
+    pub code: S3ErrorCode,
+    pub message: String,
+    pub resource: Option<String>,
+    pub request_id: Option<String>,
+    pub host_id: Option<String>,
+    /// The bucket region, set for redirect and AuthorizationHeaderMalformed errors
+    pub region: Option<String>,
+    /// The endpoint to use, set for redirect errors
+    pub endpoint: Option<String>,
+    /// Other elements of the error, e.g. Key, BucketName or Condition
+    pub extra: Vec<(String, String)>,
+}
+
+impl S3Error {
+    /// Create the error from a non-2xx response, the body is consumed
+    pub async fn from_response(head: &Parts, body: &mut IncomingBody) -> Result<Self> {
+        let mut data = Vec::<u8>::new();
+        body.read_to_end(&mut data).await?;
+
+        Ok(Self::from_parts(head, &data))
+    }
+
+    /// Create the error from the head and the buffered body of a response
+    fn from_parts(head: &Parts, data: &[u8]) -> Self {
+        let mut error = Self {
+            status: head.status,
+            code: s3_error_code_from_status(head.status),
+            message: String::new(),
+            resource: None,
+            request_id: head
+                .headers
+                .get("x-amz-request-id")
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string()),
+            host_id: head
+                .headers
+                .get("x-amz-id-2")
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string()),
+            region: head
+                .headers
+                .get("x-amz-bucket-region")
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string()),
+            endpoint: None,
+            extra: Vec::new(),
+        };
+
+        // HEAD requests and some proxies return errors without an xml body
+        if !data.is_empty() && error.parse_body(data).is_err() {
+            error.message = String::from_utf8_lossy(data).to_string();
+        }
+
+        error
+    }
+
+    /// Get the error of a 200 response with an `<Error>` body
+    ///
+    /// CopyObject, UploadPartCopy and CompleteMultipartUpload can fail after
+    /// the 200 status is sent, the error is then in the body. Returns `None`
+    /// if the root element of the buffered body isn't `<Error>`.
+    pub fn from_ok_body(data: &[u8]) -> Option<Self> {
+        let mut parser = EventReader::new(data);
+        let is_error = loop {
+            match parser.next().ok()? {
+                XmlEvent::StartElement { name, .. } => break name.local_name == "Error",
+                XmlEvent::EndDocument => return None,
+                _ => {}
+            }
+        };
+        if !is_error {
+            return None;
+        }
+
+        let mut error = Self {
+            status: StatusCode::OK,
+            code: S3ErrorCode::Unknown(String::new()),
+            message: String::new(),
+            resource: None,
+            request_id: None,
+            host_id: None,
+            region: None,
+            endpoint: None,
+            extra: Vec::new(),
+        };
+        if error.parse_body(data).is_err() {
+            error.message = String::from_utf8_lossy(data).to_string();
+        }
+
+        Some(error)
+    }
+
+    fn parse_body(&mut self, data: &[u8]) -> Result<()> {
+        let mut parser = EventReader::new(data);
+        loop {
+            match parser.next()? {
+                XmlEvent::EndDocument => break,
+
+                XmlEvent::StartElement { name, .. } if name.local_name == "Error" => {}
+                XmlEvent::StartElement { name, .. } if name.local_name == "Code" => {
+                    self.code = s3_error_code_from_str(&parse_xml_string(&mut parser, "Code")?);
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "Message" => {
+                    self.message =
+                        parse_xml_optional_string(&mut parser, "Message")?.unwrap_or_default();
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "Resource" => {
+                    self.resource = parse_xml_optional_string(&mut parser, "Resource")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "RequestId" => {
+                    self.request_id = parse_xml_optional_string(&mut parser, "RequestId")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "HostId" => {
+                    self.host_id = parse_xml_optional_string(&mut parser, "HostId")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "Region" => {
+                    self.region = parse_xml_optional_string(&mut parser, "Region")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "Endpoint" => {
+                    self.endpoint = parse_xml_optional_string(&mut parser, "Endpoint")?;
+                }
+                XmlEvent::StartElement { name, .. } => {
+                    if let Some(value) = parse_xml_optional_string(&mut parser, &name.local_name)? {
+                        self.extra.push((name.local_name, value));
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for S3Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.code, self.status)?;
+        if !self.message.is_empty() {
+            write!(f, ": {}", self.message)?;
+        }
+        if let Some(request_id) = &self.request_id {
+            write!(f, ", request id: {request_id}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for S3Error {}
+
+#[cfg(test)]
+mod tests {
+    use http::Response;
+
+    use super::*;
+
+    fn get_head(status: StatusCode) -> Parts {
+        let (head, _) = Response::builder()
+            .status(status)
+            .header("x-amz-request-id", "HEADERREQUESTID")
+            .body(())
+            .unwrap()
+            .into_parts();
+        head
+    }
+
+    #[test]
+    fn parse_error_body() {
+        let body = br#"<?xml version="1.0" encoding="UTF-8"?>
+<Error>
+  <Code>NoSuchKey</Code>
+  <Message>The resource you requested does not exist</Message>
+  <Key>myobject</Key>
+  <Resource>/mybucket/myobject</Resource>
+  <RequestId>4442587FB7D0A2F9</RequestId>
+  <HostId>host-id</HostId>
+</Error>"#;
+        let error = S3Error::from_parts(&get_head(StatusCode::NOT_FOUND), body);
+
+        assert_eq!(error.status, StatusCode::NOT_FOUND);
+        assert_eq!(error.code, S3ErrorCode::NoSuchKey);
+        assert_eq!(error.message, "The resource you requested does not exist");
+        assert_eq!(error.resource.as_deref(), Some("/mybucket/myobject"));
+        assert_eq!(error.request_id.as_deref(), Some("4442587FB7D0A2F9"));
+        assert_eq!(error.host_id.as_deref(), Some("host-id"));
+        assert_eq!(
+            error.extra,
+            vec![("Key".to_string(), "myobject".to_string())]
+        );
+    }
+
+    #[test]
+    fn parse_redirect_body() {
+        let body = b"<Error><Code>PermanentRedirect</Code><Message>Use the endpoint</Message>\
+<Endpoint>mybucket.s3.eu-north-1.amazonaws.com</Endpoint><Region>eu-north-1</Region></Error>";
+        let error = S3Error::from_parts(&get_head(StatusCode::MOVED_PERMANENTLY), body);
+
+        assert_eq!(error.code, S3ErrorCode::PermanentRedirect);
+        assert_eq!(error.region.as_deref(), Some("eu-north-1"));
+        assert_eq!(
+            error.endpoint.as_deref(),
+            Some("mybucket.s3.eu-north-1.amazonaws.com")
+        );
+    }
+
+    #[test]
+    fn parse_unknown_code() {
+        let body = b"<Error><Code>SomeNewError</Code><Message></Message></Error>";
+        let error = S3Error::from_parts(&get_head(StatusCode::BAD_REQUEST), body);
+
+        assert_eq!(error.code, S3ErrorCode::Unknown("SomeNewError".to_string()));
+        assert_eq!(error.code.to_string(), "SomeNewError");
+        assert_eq!(error.message, "");
+    }
+
+    #[test]
+    fn parse_empty_body() {
+        let error = S3Error::from_parts(&get_head(StatusCode::NOT_FOUND), b"");
+
+        assert_eq!(error.code, S3ErrorCode::NotFound);
+        assert_eq!(error.message, "");
+        assert_eq!(error.request_id.as_deref(), Some("HEADERREQUESTID"));
+    }
+
+    #[test]
+    fn parse_malformed_body() {
+        let body = b"<html><body>Bad Gateway";
+        let error = S3Error::from_parts(&get_head(StatusCode::BAD_GATEWAY), body);
+
+        assert_eq!(error.code, S3ErrorCode::Unknown("502".to_string()));
+        assert_eq!(error.message, "<html><body>Bad Gateway");
+    }
+
+    #[test]
+    fn ok_error_body() {
+        // The CompleteMultipartUpload and CopyObject error of a 200 response
+        let body = br#"<?xml version="1.0" encoding="UTF-8"?>
+<Error>
+  <Code>InternalError</Code>
+  <Message>We encountered an internal error. Please try again.</Message>
+  <RequestId>656c76696e6727732072657175657374</RequestId>
+</Error>"#;
+        let error = S3Error::from_ok_body(body).unwrap();
+
+        assert_eq!(error.status, StatusCode::OK);
+        assert_eq!(error.code, S3ErrorCode::InternalError);
+        assert_eq!(
+            error.request_id.as_deref(),
+            Some("656c76696e6727732072657175657374")
+        );
+    }
+
+    #[test]
+    fn ok_body_without_error() {
+        let body = br#"<?xml version="1.0" encoding="UTF-8"?>
+<CopyObjectResult><ETag>"9b2cf535f27731c974343645a3985328"</ETag></CopyObjectResult>"#;
+
+        assert!(S3Error::from_ok_body(body).is_none());
+        assert!(S3Error::from_ok_body(b"").is_none());
+        assert!(S3Error::from_ok_body(b"not xml").is_none());
+    }
+
+    #[test]
+    fn ok_malformed_error_body() {
+        let body = b"<Error><Code>InternalError</Code><Message>truncated";
+        let error = S3Error::from_ok_body(body).unwrap();
+
+        assert_eq!(
+            error.message,
+            "<Error><Code>InternalError</Code><Message>truncated"
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use anyhow::{anyhow, Result};
+use wstd::http::{body::IncomingBody, Method};
+
+use super::{S3RequestBuilder, S3RequestData, S3ResponseData};
+
+/// Upload a part of a multipart upload
+///
+/// The ETag of the uploaded part is only returned as a response header, see
+/// [super::complete_multipart_upload::CompletedPart::from_headers].
+pub struct UploadPartRequest {
+    pub key: String,
+    pub upload_id: String,
+    pub part_number: u32,
+    pub body: Vec<u8>,
+}
+
+impl S3RequestData for UploadPartRequest {
+    type ResponseType = UploadPartResponse;
+
+    fn into_builder(
+        &self,
+        access_key: &str,
+        secret_key: &str,
+        region: &str,
+        endpoint: &str,
+    ) -> Result<S3RequestBuilder<Self::ResponseType>> {
+        let mut builder = S3RequestBuilder::new(
+            Method::PUT,
+            &self.key,
+            access_key,
+            secret_key,
+            region,
+            endpoint,
+        );
+
+        if !(1..=10000).contains(&self.part_number) {
+            return Err(anyhow!("part_number has to be constrained to part_number >= 1 and part_number <= 10000, part_number is {}", self.part_number));
+        }
+        builder.query("partNumber", Some(&self.part_number.to_string()));
+        builder.query("uploadId", Some(&self.upload_id));
+        builder.body(&self.body);
+
+        Ok(builder)
+    }
+}
+
+pub struct UploadPartResponse {}
+
+impl S3ResponseData for UploadPartResponse {
+    async fn parse_body(_response: &mut IncomingBody) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(Self {})
+    }
+}
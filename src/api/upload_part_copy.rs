@@ -0,0 +1,137 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use wstd::{
+    http::{body::IncomingBody, Method},
+    io::AsyncRead,
+};
+use xml::reader::{EventReader, XmlEvent};
+
+use super::{
+    complete_multipart_upload::CompletedPart, conditional_headers::ConditionalHeaders,
+    copy_object::CopySource, parse_xml_checksum, parse_xml_string, s3_error::S3Error,
+    x_amz_headers::XAmzChecksum, S3RequestBuilder, S3RequestData, S3ResponseData,
+    XML_CHECKSUM_FIELDS,
+};
+
+/// Copy a byte range of an object as a part of a multipart upload
+pub struct UploadPartCopyRequest {
+    pub key: String,
+    pub upload_id: String,
+    pub part_number: u32,
+    pub source: CopySource,
+    /// The inclusive byte range (first, last) of the source object to copy
+    pub source_range: Option<(u64, u64)>,
+    /// Conditions on the source object, sent as x-amz-copy-source-if-* headers
+    pub source_conditions: Option<ConditionalHeaders>,
+}
+
+impl S3RequestData for UploadPartCopyRequest {
+    type ResponseType = UploadPartCopyResponse;
+
+    fn into_builder(
+        &self,
+        access_key: &str,
+        secret_key: &str,
+        region: &str,
+        endpoint: &str,
+    ) -> Result<S3RequestBuilder<Self::ResponseType>> {
+        let mut builder = S3RequestBuilder::new(
+            Method::PUT,
+            &self.key,
+            access_key,
+            secret_key,
+            region,
+            endpoint,
+        );
+
+        if !(1..=10000).contains(&self.part_number) {
+            return Err(anyhow!("part_number has to be constrained to part_number >= 1 and part_number <= 10000, part_number is {}", self.part_number));
+        }
+        builder.query("partNumber", Some(&self.part_number.to_string()));
+        builder.query("uploadId", Some(&self.upload_id));
+        builder.header("x-amz-copy-source", &self.source.get_header_value());
+
+        if let Some((first, last)) = self.source_range {
+            if first > last {
+                return Err(anyhow!(
+                    "source_range first byte has to be <= last byte, range is {first}-{last}"
+                ));
+            }
+            builder.header("x-amz-copy-source-range", &format!("bytes={first}-{last}"));
+        }
+        if let Some(conditions) = &self.source_conditions {
+            builder.headers(conditions.get_copy_source_headers());
+        }
+
+        Ok(builder)
+    }
+}
+
+pub struct UploadPartCopyResponse {
+    pub etag: String,
+    pub last_modified: Option<DateTime<Utc>>,
+    pub checksum: Option<XAmzChecksum>,
+}
+
+impl UploadPartCopyResponse {
+    /// Use the copied part in a [super::complete_multipart_upload::CompleteMultipartUploadRequest]
+    pub fn into_completed_part(self, part_number: u32) -> CompletedPart {
+        CompletedPart {
+            part_number,
+            etag: self.etag,
+            checksum: self.checksum,
+        }
+    }
+}
+
+impl S3ResponseData for UploadPartCopyResponse {
+    async fn parse_body(response: &mut IncomingBody) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut data = Vec::<u8>::new();
+        response.read_to_end(&mut data).await?;
+        // S3 can fail the copy after sending the 200 status
+        if let Some(error) = S3Error::from_ok_body(&data) {
+            return Err(error.into());
+        }
+        let mut parser = EventReader::new(data.as_slice());
+
+        let mut copy_response = UploadPartCopyResponse {
+            etag: String::new(),
+            last_modified: None,
+            checksum: None,
+        };
+        loop {
+            match parser.next()? {
+                XmlEvent::EndDocument => break,
+
+                XmlEvent::StartElement { name, .. } if name.local_name == "ETag" => {
+                    copy_response.etag = parse_xml_string(&mut parser, "ETag")?;
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "LastModified" => {
+                    let datetime = DateTime::parse_from_rfc3339(&parse_xml_string(
+                        &mut parser,
+                        "LastModified",
+                    )?)?
+                    .to_utc();
+                    copy_response.last_modified = Some(datetime);
+                }
+                XmlEvent::StartElement { name, .. }
+                    if XML_CHECKSUM_FIELDS.contains(&name.local_name.as_str()) =>
+                {
+                    copy_response.checksum =
+                        Some(parse_xml_checksum(&mut parser, &name.local_name)?);
+                }
+
+                _ => {}
+            }
+        }
+
+        if copy_response.etag.is_empty() {
+            return Err(anyhow!("Invalid response object, ETag is missing"));
+        }
+
+        Ok(copy_response)
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use base64::{prelude::BASE64_STANDARD, Engine};
+
+pub(crate) const USER_METADATA_PREFIX: &str = "x-amz-meta-";
+/// S3 limits the user metadata to 2 KB, the utf-8 size of all keys and values
+pub const MAX_USER_METADATA_SIZE: usize = 2 * 1024;
+/// Keeps every RFC 2047 encoded word within the 75 character limit
+const ENCODED_WORD_MAX_BYTES: usize = 45;
+
+/// Get the x-amz-meta-* headers of the user metadata
+///
+/// Keys are lowercased and have to be valid header names, values that
+/// aren't printable ascii are RFC 2047 encoded.
+pub(crate) fn get_metadata_headers(
+    metadata: &HashMap<String, String>,
+) -> Result<Vec<(String, String)>> {
+    let size: usize = metadata.iter().map(|(k, v)| k.len() + v.len()).sum();
+    if size > MAX_USER_METADATA_SIZE {
+        return Err(anyhow!(
+            "user metadata can be at most {MAX_USER_METADATA_SIZE} bytes, metadata is {size} bytes"
+        ));
+    }
+
+    let mut headers = Vec::new();
+    for (key, value) in metadata {
+        if key.is_empty() || !key.chars().all(is_token_char) {
+            return Err(anyhow!(
+                "user metadata keys have to be valid header names, key is {key:?}"
+            ));
+        }
+        headers.push((
+            format!("{USER_METADATA_PREFIX}{}", key.to_lowercase()),
+            encode_metadata_value(value),
+        ));
+    }
+    // Keep the request deterministic regardless of the map order
+    headers.sort();
+
+    Ok(headers)
+}
+
+/// The header name characters of RFC 9110
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+/// Encode a value as RFC 2047 `=?UTF-8?B?...?=` words if it isn't printable ascii
+pub(crate) fn encode_metadata_value(value: &str) -> String {
+    let value = value.trim();
+    if value.chars().all(|c| c.is_ascii() && !c.is_ascii_control()) {
+        return value.to_owned();
+    }
+
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for c in value.chars() {
+        if word.len() + c.len_utf8() > ENCODED_WORD_MAX_BYTES {
+            words.push(format!("=?UTF-8?B?{}?=", BASE64_STANDARD.encode(&word)));
+            word.clear();
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(format!("=?UTF-8?B?{}?=", BASE64_STANDARD.encode(&word)));
+    }
+
+    words.join(" ")
+}
+
+/// Decode the RFC 2047 encoded words of a metadata value
+///
+/// Only utf-8 B and Q encodings are decoded, the whitespace between adjacent
+/// encoded words is dropped. Values that can't be decoded are returned as is.
+pub(crate) fn decode_metadata_value(value: &str) -> String {
+    if !value.contains("=?") {
+        return value.to_owned();
+    }
+
+    let mut decoded = String::new();
+    let mut previous_encoded = false;
+    for (i, word) in value.split(' ').enumerate() {
+        match decode_encoded_word(word) {
+            Some(text) => {
+                if i > 0 && !previous_encoded {
+                    decoded.push(' ');
+                }
+                decoded.push_str(&text);
+                previous_encoded = true;
+            }
+            None => {
+                if i > 0 {
+                    decoded.push(' ');
+                }
+                decoded.push_str(word);
+                previous_encoded = false;
+            }
+        }
+    }
+
+    decoded
+}
+
+fn decode_encoded_word(word: &str) -> Option<String> {
+    let inner = word.strip_prefix("=?")?.strip_suffix("?=")?;
+    let mut parts = inner.splitn(3, '?');
+    let (charset, encoding, text) = (parts.next()?, parts.next()?, parts.next()?);
+    if !charset.eq_ignore_ascii_case("utf-8") {
+        return None;
+    }
+
+    let bytes = match encoding {
+        "B" | "b" => BASE64_STANDARD.decode(text).ok()?,
+        "Q" | "q" => {
+            let mut bytes = Vec::new();
+            let mut chars = text.bytes();
+            while let Some(b) = chars.next() {
+                match b {
+                    b'_' => bytes.push(b' '),
+                    b'=' => {
+                        let hex = [chars.next()?, chars.next()?];
+                        bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+                    }
+                    b => bytes.push(b),
+                }
+            }
+            bytes
+        }
+        _ => return None,
+    };
+
+    String::from_utf8(bytes).ok()
+}
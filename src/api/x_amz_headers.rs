@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use wstd::http::HeaderMap;
 
 pub enum XAmzCannedAcl {
     Private,
@@ -39,6 +40,15 @@ pub enum XAmzServerSideEncryption {
     Algorithm(String),
 }
 
+pub enum XAmzReplicationStatus {
+    Complete,
+    Pending,
+    Failed,
+    Replica,
+    Completed,
+    Status(String),
+}
+
 pub enum XAmzStorageClass {
     Standard,
     ReducedRedundancy,
@@ -71,6 +81,78 @@ pub(crate) fn storage_class_from_str(class: String) -> XAmzStorageClass {
     }
 }
 
+pub(crate) fn server_side_encryption_from_str(algorithm: &str) -> XAmzServerSideEncryption {
+    match algorithm.to_lowercase() {
+        a if a == "aes256" => XAmzServerSideEncryption::AES256,
+        a if a == "aws:kms" => XAmzServerSideEncryption::KMS,
+        a if a == "aws:kms:dsse" => XAmzServerSideEncryption::KMSDSSE,
+
+        _ => XAmzServerSideEncryption::Algorithm(algorithm.to_owned()),
+    }
+}
+
+pub(crate) fn object_lock_mode_from_str(mode: &str) -> Option<XAmzObjectLockMode> {
+    match mode.to_lowercase() {
+        m if m == "governance" => Some(XAmzObjectLockMode::Governance),
+        m if m == "compliance" => Some(XAmzObjectLockMode::Compliance),
+
+        _ => None,
+    }
+}
+
+pub(crate) fn replication_status_from_str(status: &str) -> XAmzReplicationStatus {
+    match status.to_lowercase() {
+        s if s == "complete" => XAmzReplicationStatus::Complete,
+        s if s == "pending" => XAmzReplicationStatus::Pending,
+        s if s == "failed" => XAmzReplicationStatus::Failed,
+        s if s == "replica" => XAmzReplicationStatus::Replica,
+        s if s == "completed" => XAmzReplicationStatus::Completed,
+
+        _ => XAmzReplicationStatus::Status(status.to_owned()),
+    }
+}
+
+/// Read the `x-amz-checksum-*` header from a response, if any
+pub(crate) fn checksum_from_headers(headers: &HeaderMap) -> Option<XAmzChecksum> {
+    for (key, value) in headers {
+        let Some(algorithm) = key.as_str().strip_prefix("x-amz-checksum-") else {
+            continue;
+        };
+        let Ok(sum) = value.to_str() else {
+            continue;
+        };
+        let sum = sum.to_owned();
+
+        let checksum = match algorithm {
+            "crc32" => XAmzChecksum::CRC32(sum),
+            "crc32c" => XAmzChecksum::CRC32C(sum),
+            "crc64nvme" => XAmzChecksum::CRC64NVME(sum),
+            "sha1" => XAmzChecksum::SHA1(sum),
+            "sha256" => XAmzChecksum::Sha256(sum),
+            // x-amz-checksum-type and x-amz-checksum-algorithm aren't checksums
+            "type" | "algorithm" | "mode" => continue,
+            a => XAmzChecksum::Checksum(a.to_owned(), sum),
+        };
+        return Some(checksum);
+    }
+
+    None
+}
+
+/// Get the x-amz-checksum-* header name and value of a checksum
+pub(crate) fn checksum_to_header(checksum: &XAmzChecksum) -> (String, String) {
+    let (key, sum) = match checksum {
+        XAmzChecksum::CRC32(sum) => ("crc32", sum),
+        XAmzChecksum::CRC32C(sum) => ("crc32c", sum),
+        XAmzChecksum::CRC64NVME(sum) => ("crc64nvme", sum),
+        XAmzChecksum::SHA1(sum) => ("sha1", sum),
+        XAmzChecksum::Sha256(sum) => ("sha256", sum),
+        XAmzChecksum::Checksum(k, sum) => (k.as_str(), sum),
+    };
+
+    (format!("x-amz-checksum-{key}"), sum.to_owned())
+}
+
 /// Set x-amz headers on a request
 ///
 /// see [super::S3RequestBuilder::set_x_amz_headers]
@@ -284,21 +366,7 @@ impl XAmzHeadersBuilder {
         }
     }
     fn get_checksum_header(&self) -> Option<(String, String)> {
-        match &self.checksum {
-            Some(checksum) => {
-                let (key, check) = match checksum {
-                    XAmzChecksum::CRC32(sum) => ("crc32".to_string(), sum),
-                    XAmzChecksum::CRC32C(sum) => ("crc32c".to_string(), sum),
-                    XAmzChecksum::CRC64NVME(sum) => ("crc64nvme".to_string(), sum),
-                    XAmzChecksum::SHA1(sum) => ("sha1".to_string(), sum),
-                    XAmzChecksum::Sha256(sum) => ("sha256".to_string(), sum),
-                    XAmzChecksum::Checksum(k, sum) => (k.to_owned(), sum),
-                };
-
-                Some((format!("x-amz-checksum-{key}"), check.to_owned()))
-            }
-            None => None,
-        }
+        self.checksum.as_ref().map(checksum_to_header)
     }
     fn get_grants_headers(&self) -> Vec<(String, String)> {
         let mut headers = Vec::new();
@@ -0,0 +1,351 @@
+use anyhow::{anyhow, Result};
+
+use crate::AWS_SERVICE;
+
+const OBJECT_LAMBDA_SERVICE: &str = "s3-object-lambda";
+const OUTPOSTS_SERVICE: &str = "s3-outposts";
+
+/// The resource of an S3 ARN
+pub enum ArnResource {
+    /// `accesspoint/{name}` of an S3 access point
+    AccessPoint { name: String },
+    /// `accesspoint/{name}` of an S3 Object Lambda access point
+    ObjectLambdaAccessPoint { name: String },
+    /// `outpost/{outpost_id}/accesspoint/{name}` of an S3 on Outposts access point
+    OutpostAccessPoint { outpost_id: String, name: String },
+}
+
+/// An S3 access point ARN that can be used instead of a bucket name
+///
+/// Requests to an ARN are sent to the host of the access point and signed
+/// for the region and service of the ARN. Multi-Region access points aren't
+/// supported since they are signed with SigV4A.
+///
+/// # Examples
+/// ```
+/// # use s3_wasi_http::arn::S3Arn;
+/// let arn = S3Arn::parse("arn:aws:s3:eu-north-1:123456789012:accesspoint/my-access-point")?;
+/// assert_eq!(
+///     arn.resolve_endpoint(false, false)?,
+///     "my-access-point-123456789012.s3-accesspoint.eu-north-1.amazonaws.com"
+/// );
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct S3Arn {
+    /// `aws`, `aws-cn` or `aws-us-gov`
+    pub partition: String,
+    pub region: String,
+    pub account_id: String,
+    pub resource: ArnResource,
+}
+
+/// Check if a bucket is an ARN
+pub(crate) fn is_arn(bucket: &str) -> bool {
+    bucket.starts_with("arn:")
+}
+
+/// Split the resource into its parts, both `/` and `:` are valid separators
+fn split_resource(resource: &str) -> Vec<&str> {
+    resource.split(['/', ':']).collect()
+}
+
+/// Access point names are used in the host name, 3 to 50 lowercase letters,
+/// digits and hyphens
+fn validate_access_point_name(name: &str) -> Result<()> {
+    if !(3..=50).contains(&name.len())
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        || name.starts_with('-')
+        || name.ends_with('-')
+    {
+        return Err(anyhow!(
+            "access point name \"{name}\" has to be 3 to 50 lowercase letters, digits and hyphens"
+        ));
+    }
+
+    Ok(())
+}
+
+impl S3Arn {
+    /// Parse an `arn:{partition}:{service}:{region}:{account}:{resource}` ARN
+    pub fn parse(arn: &str) -> Result<Self> {
+        let mut parts = arn.splitn(6, ':');
+        let (
+            Some("arn"),
+            Some(partition),
+            Some(service),
+            Some(region),
+            Some(account_id),
+            Some(resource),
+        ) = (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        )
+        else {
+            return Err(anyhow!("\"{arn}\" isn't a valid ARN"));
+        };
+
+        if !matches!(partition, "aws" | "aws-cn" | "aws-us-gov") {
+            return Err(anyhow!(
+                "unsupported partition \"{partition}\" in ARN \"{arn}\""
+            ));
+        }
+        if region.is_empty() {
+            return Err(anyhow!(
+                "ARN \"{arn}\" has no region, Multi-Region access points aren't supported"
+            ));
+        }
+        if account_id.len() != 12 || !account_id.chars().all(|c| c.is_ascii_digit()) {
+            return Err(anyhow!(
+                "ARN \"{arn}\" has an invalid account id \"{account_id}\""
+            ));
+        }
+
+        let resource = match (service, split_resource(resource).as_slice()) {
+            (AWS_SERVICE, ["accesspoint", name]) => ArnResource::AccessPoint {
+                name: name.to_string(),
+            },
+            (OBJECT_LAMBDA_SERVICE, ["accesspoint", name]) => {
+                ArnResource::ObjectLambdaAccessPoint {
+                    name: name.to_string(),
+                }
+            }
+            (OUTPOSTS_SERVICE, ["outpost", outpost_id, "accesspoint", name]) => {
+                ArnResource::OutpostAccessPoint {
+                    outpost_id: outpost_id.to_string(),
+                    name: name.to_string(),
+                }
+            }
+            _ => {
+                return Err(anyhow!(
+                    "ARN \"{arn}\" isn't an S3 access point, Object Lambda or Outposts access point ARN"
+                ))
+            }
+        };
+        match &resource {
+            ArnResource::AccessPoint { name } | ArnResource::ObjectLambdaAccessPoint { name } => {
+                validate_access_point_name(name)?
+            }
+            ArnResource::OutpostAccessPoint { outpost_id, name } => {
+                validate_access_point_name(name)?;
+                if outpost_id.is_empty()
+                    || !outpost_id
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-')
+                {
+                    return Err(anyhow!(
+                        "ARN \"{arn}\" has an invalid outpost id \"{outpost_id}\""
+                    ));
+                }
+            }
+        }
+
+        Ok(Self {
+            partition: partition.to_owned(),
+            region: region.to_owned(),
+            account_id: account_id.to_owned(),
+            resource,
+        })
+    }
+
+    /// The service name requests to the access point are signed with
+    pub fn signing_service(&self) -> &'static str {
+        match self.resource {
+            ArnResource::AccessPoint { .. } => AWS_SERVICE,
+            ArnResource::ObjectLambdaAccessPoint { .. } => OBJECT_LAMBDA_SERVICE,
+            ArnResource::OutpostAccessPoint { .. } => OUTPOSTS_SERVICE,
+        }
+    }
+
+    fn get_dns_suffix(&self) -> &'static str {
+        match self.partition.as_str() {
+            "aws-cn" => "amazonaws.com.cn",
+            _ => "amazonaws.com",
+        }
+    }
+
+    /// Get the host of the access point
+    ///
+    /// `{name}-{account}.s3-accesspoint.{region}.amazonaws.com` for access
+    /// points, `{name}-{account}.s3-object-lambda.{region}.amazonaws.com` for
+    /// Object Lambda and `{name}-{account}.{outpost}.s3-outposts.{region}.amazonaws.com`
+    /// for Outposts access points. Object Lambda has no dual-stack and
+    /// Outposts no FIPS or dual-stack endpoints.
+    pub fn resolve_endpoint(&self, fips: bool, dual_stack: bool) -> Result<String> {
+        let fips_suffix = match fips {
+            true => "-fips",
+            false => "",
+        };
+        let dual_stack_label = match dual_stack {
+            true => ".dualstack",
+            false => "",
+        };
+        let region = &self.region;
+        let suffix = self.get_dns_suffix();
+        if fips && self.partition == "aws-cn" {
+            return Err(anyhow!("FIPS endpoints aren't available in {region}"));
+        }
+
+        match &self.resource {
+            ArnResource::AccessPoint { name } => Ok(format!(
+                "{name}-{}.s3-accesspoint{fips_suffix}{dual_stack_label}.{region}.{suffix}",
+                self.account_id
+            )),
+            ArnResource::ObjectLambdaAccessPoint { name } => {
+                if dual_stack {
+                    return Err(anyhow!(
+                        "Object Lambda access points don't support dual-stack endpoints"
+                    ));
+                }
+                Ok(format!(
+                    "{name}-{}.{OBJECT_LAMBDA_SERVICE}{fips_suffix}.{region}.{suffix}",
+                    self.account_id
+                ))
+            }
+            ArnResource::OutpostAccessPoint { outpost_id, name } => {
+                if fips || dual_stack {
+                    return Err(anyhow!(
+                        "Outposts access points don't support FIPS or dual-stack endpoints"
+                    ));
+                }
+                Ok(format!(
+                    "{name}-{}.{outpost_id}.{OUTPOSTS_SERVICE}.{region}.{suffix}",
+                    self.account_id
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_access_point() {
+        let arn =
+            S3Arn::parse("arn:aws:s3:eu-north-1:123456789012:accesspoint/my-access-point").unwrap();
+        assert_eq!(arn.partition, "aws");
+        assert_eq!(arn.region, "eu-north-1");
+        assert_eq!(arn.account_id, "123456789012");
+        assert!(
+            matches!(arn.resource, ArnResource::AccessPoint { ref name } if name == "my-access-point")
+        );
+        assert_eq!(arn.signing_service(), "s3");
+
+        // `:` is a valid resource separator as well
+        let arn =
+            S3Arn::parse("arn:aws:s3:eu-north-1:123456789012:accesspoint:my-access-point").unwrap();
+        assert!(
+            matches!(arn.resource, ArnResource::AccessPoint { ref name } if name == "my-access-point")
+        );
+    }
+
+    #[test]
+    fn parse_object_lambda_access_point() {
+        let arn =
+            S3Arn::parse("arn:aws:s3-object-lambda:us-west-2:123456789012:accesspoint/my-banner")
+                .unwrap();
+        assert!(
+            matches!(arn.resource, ArnResource::ObjectLambdaAccessPoint { ref name } if name == "my-banner")
+        );
+        assert_eq!(arn.signing_service(), "s3-object-lambda");
+    }
+
+    #[test]
+    fn parse_outposts_access_point() {
+        let arn = S3Arn::parse(
+            "arn:aws:s3-outposts:us-west-2:123456789012:outpost/op-01ac5d28a6a232904/accesspoint/my-outpost-ap",
+        )
+        .unwrap();
+        assert!(matches!(
+            arn.resource,
+            ArnResource::OutpostAccessPoint { ref outpost_id, ref name }
+                if outpost_id == "op-01ac5d28a6a232904" && name == "my-outpost-ap"
+        ));
+        assert_eq!(arn.signing_service(), "s3-outposts");
+    }
+
+    #[test]
+    fn parse_malformed() {
+        let arns = [
+            "my-access-point",
+            "arn:aws:s3:eu-north-1:123456789012",
+            "arn:aws-xx:s3:eu-north-1:123456789012:accesspoint/my-access-point",
+            // Multi-Region access points have no region
+            "arn:aws:s3::123456789012:accesspoint/mfzwi23gnjvgw.mrap",
+            "arn:aws:s3:eu-north-1:1234:accesspoint/my-access-point",
+            "arn:aws:s3:eu-north-1:123456789012:bucket/mybucket",
+            "arn:aws:sqs:eu-north-1:123456789012:accesspoint/my-access-point",
+            "arn:aws:s3:eu-north-1:123456789012:accesspoint/My_Access_Point",
+            "arn:aws:s3:eu-north-1:123456789012:accesspoint/ap",
+            "arn:aws:s3-outposts:us-west-2:123456789012:outpost/op_1/accesspoint/my-outpost-ap",
+            "arn:aws:s3-outposts:us-west-2:123456789012:accesspoint/my-outpost-ap",
+        ];
+        for arn in arns {
+            assert!(S3Arn::parse(arn).is_err(), "{arn}");
+        }
+    }
+
+    fn resolve(arn: &str, fips: bool, dual_stack: bool) -> Result<String> {
+        S3Arn::parse(arn)?.resolve_endpoint(fips, dual_stack)
+    }
+
+    #[test]
+    fn access_point_hosts() {
+        let arn = "arn:aws:s3:eu-north-1:123456789012:accesspoint/my-access-point";
+        assert_eq!(
+            resolve(arn, false, false).unwrap(),
+            "my-access-point-123456789012.s3-accesspoint.eu-north-1.amazonaws.com"
+        );
+        assert_eq!(
+            resolve(arn, true, true).unwrap(),
+            "my-access-point-123456789012.s3-accesspoint-fips.dualstack.eu-north-1.amazonaws.com"
+        );
+        assert_eq!(
+            resolve(
+                "arn:aws-cn:s3:cn-north-1:123456789012:accesspoint/my-access-point",
+                false,
+                false
+            )
+            .unwrap(),
+            "my-access-point-123456789012.s3-accesspoint.cn-north-1.amazonaws.com.cn"
+        );
+        assert!(resolve(
+            "arn:aws-cn:s3:cn-north-1:123456789012:accesspoint/my-access-point",
+            true,
+            false
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn object_lambda_hosts() {
+        let arn = "arn:aws:s3-object-lambda:us-west-2:123456789012:accesspoint/my-banner";
+        assert_eq!(
+            resolve(arn, false, false).unwrap(),
+            "my-banner-123456789012.s3-object-lambda.us-west-2.amazonaws.com"
+        );
+        assert_eq!(
+            resolve(arn, true, false).unwrap(),
+            "my-banner-123456789012.s3-object-lambda-fips.us-west-2.amazonaws.com"
+        );
+        assert!(resolve(arn, false, true).is_err());
+    }
+
+    #[test]
+    fn outposts_hosts() {
+        let arn = "arn:aws:s3-outposts:us-west-2:123456789012:outpost/op-01ac5d28a6a232904/accesspoint/my-outpost-ap";
+        assert_eq!(
+            resolve(arn, false, false).unwrap(),
+            "my-outpost-ap-123456789012.op-01ac5d28a6a232904.s3-outposts.us-west-2.amazonaws.com"
+        );
+        assert!(resolve(arn, true, false).is_err());
+        assert!(resolve(arn, false, true).is_err());
+    }
+}
@@ -0,0 +1,344 @@
+use std::{cell::RefCell, env, future::Future, pin::Pin, time::Duration};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+
+pub mod metadata;
+pub mod profile;
+pub mod sts;
+
+/// Refresh cached credentials this long before they expire
+const DEFAULT_REFRESH_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+/// The future returned by [CredentialsProvider::provide_credentials]
+pub type CredentialsFuture<'a> = Pin<Box<dyn Future<Output = Result<Credentials>> + 'a>>;
+
+/// Credentials used to sign requests
+#[derive(Clone)]
+pub struct Credentials {
+    pub access_key: String,
+    pub secret_key: String,
+    /// Required for temporary credentials, sent as x-amz-security-token
+    pub session_token: Option<String>,
+    /// When the credentials expire, `None` if they never expire
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl Credentials {
+    pub fn new(access_key: &str, secret_key: &str) -> Self {
+        Self {
+            access_key: access_key.to_owned(),
+            secret_key: secret_key.to_owned(),
+            session_token: None,
+            expires_at: None,
+        }
+    }
+
+    /// Check if the credentials expire within `window`
+    pub fn expires_within(&self, window: Duration) -> bool {
+        match self.expires_at {
+            Some(expires_at) => expires_at - window <= Utc::now(),
+            None => false,
+        }
+    }
+}
+
+/// Provides the credentials used by [crate::S3Client] to sign requests
+///
+/// The credentials are requested every time a request is created, wrap
+/// providers that are expensive to call in a [CachedCredentialsProvider].
+///
+/// # Examples
+/// ```no_run
+/// # use s3_wasi_http::credentials::{Credentials, CredentialsFuture, CredentialsProvider};
+/// struct MyProvider;
+///
+/// impl CredentialsProvider for MyProvider {
+///     fn provide_credentials(&self) -> CredentialsFuture<'_> {
+///         Box::pin(async { Ok(Credentials::new("access_key", "secret_key")) })
+///     }
+/// }
+/// ```
+pub trait CredentialsProvider {
+    fn provide_credentials(&self) -> CredentialsFuture<'_>;
+}
+
+/// Always provides the same credentials
+pub struct StaticCredentialsProvider {
+    credentials: Credentials,
+}
+
+impl StaticCredentialsProvider {
+    pub fn new(credentials: Credentials) -> Self {
+        Self { credentials }
+    }
+}
+
+impl CredentialsProvider for StaticCredentialsProvider {
+    fn provide_credentials(&self) -> CredentialsFuture<'_> {
+        Box::pin(async { Ok(self.credentials.clone()) })
+    }
+}
+
+/// Provides credentials from AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY
+/// and AWS_SESSION_TOKEN
+///
+/// The envs are read every time credentials are requested.
+pub struct EnvCredentialsProvider;
+
+impl CredentialsProvider for EnvCredentialsProvider {
+    fn provide_credentials(&self) -> CredentialsFuture<'_> {
+        Box::pin(async {
+            let access_key = env::var("AWS_ACCESS_KEY_ID")
+                .map_err(|_| anyhow!("ENV \"AWS_ACCESS_KEY_ID\" isn't set"))?;
+            let secret_key = env::var("AWS_SECRET_ACCESS_KEY")
+                .map_err(|_| anyhow!("ENV \"AWS_SECRET_ACCESS_KEY\" isn't set"))?;
+
+            Ok(Credentials {
+                access_key,
+                secret_key,
+                session_token: env::var("AWS_SESSION_TOKEN").ok(),
+                expires_at: None,
+            })
+        })
+    }
+}
+
+/// Tries the providers in order and returns the first credentials found
+///
+/// # Examples
+/// ```no_run
+/// # use s3_wasi_http::credentials::{
+/// #     Credentials, CredentialsProviderChain, EnvCredentialsProvider, StaticCredentialsProvider,
+/// # };
+/// let chain = CredentialsProviderChain::new()
+///     .with(EnvCredentialsProvider)
+///     .with(StaticCredentialsProvider::new(Credentials::new(
+///         "access_key",
+///         "secret_key",
+///     )));
+/// ```
+#[derive(Default)]
+pub struct CredentialsProviderChain {
+    providers: Vec<Box<dyn CredentialsProvider>>,
+}
+
+impl CredentialsProviderChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a provider to the end of the chain
+    pub fn with<P>(self, provider: P) -> Self
+    where
+        P: CredentialsProvider + 'static,
+    {
+        let mut providers = self.providers;
+        providers.push(Box::new(provider));
+        Self { providers }
+    }
+}
+
+impl CredentialsProvider for CredentialsProviderChain {
+    fn provide_credentials(&self) -> CredentialsFuture<'_> {
+        Box::pin(async {
+            let mut errors = Vec::new();
+            for provider in &self.providers {
+                match provider.provide_credentials().await {
+                    Ok(credentials) => return Ok(credentials),
+                    Err(e) => errors.push(e.to_string()),
+                }
+            }
+
+            Err(anyhow!(
+                "no credentials provider in the chain returned credentials: [{}]",
+                errors.join(", ")
+            ))
+        })
+    }
+}
+
+/// Caches the credentials of a provider until they're about to expire
+///
+/// Credentials without an expiry are cached forever.
+pub struct CachedCredentialsProvider<P: CredentialsProvider> {
+    provider: P,
+    refresh_window: Duration,
+    cached: RefCell<Option<Credentials>>,
+}
+
+impl<P> CachedCredentialsProvider<P>
+where
+    P: CredentialsProvider,
+{
+    /// Cache `provider`, refreshing credentials 5 minutes before they expire
+    pub fn new(provider: P) -> Self {
+        Self {
+            provider,
+            refresh_window: DEFAULT_REFRESH_WINDOW,
+            cached: RefCell::new(None),
+        }
+    }
+
+    /// Set how long before the expiry credentials are refreshed
+    pub fn refresh_window(self, refresh_window: Duration) -> Self {
+        Self {
+            refresh_window,
+            ..self
+        }
+    }
+}
+
+impl<P> CredentialsProvider for CachedCredentialsProvider<P>
+where
+    P: CredentialsProvider,
+{
+    fn provide_credentials(&self) -> CredentialsFuture<'_> {
+        Box::pin(async {
+            if let Some(credentials) = self.cached.borrow().as_ref() {
+                if !credentials.expires_within(self.refresh_window) {
+                    return Ok(credentials.clone());
+                }
+            }
+
+            let credentials = self.provider.provide_credentials().await?;
+            *self.cached.borrow_mut() = Some(credentials.clone());
+
+            Ok(credentials)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    /// Counts the calls and returns credentials expiring after `expires_in`
+    struct CountingProvider {
+        calls: Rc<Cell<u32>>,
+        expires_in: Option<chrono::Duration>,
+    }
+
+    impl CountingProvider {
+        fn new(expires_in: Option<chrono::Duration>) -> (Self, Rc<Cell<u32>>) {
+            let calls = Rc::new(Cell::new(0));
+            let provider = Self {
+                calls: calls.clone(),
+                expires_in,
+            };
+            (provider, calls)
+        }
+    }
+
+    impl CredentialsProvider for CountingProvider {
+        fn provide_credentials(&self) -> CredentialsFuture<'_> {
+            Box::pin(async {
+                self.calls.set(self.calls.get() + 1);
+                Ok(Credentials {
+                    access_key: format!("access_key_{}", self.calls.get()),
+                    secret_key: "secret_key".to_string(),
+                    session_token: None,
+                    expires_at: self.expires_in.map(|expires_in| Utc::now() + expires_in),
+                })
+            })
+        }
+    }
+
+    struct FailingProvider;
+
+    impl CredentialsProvider for FailingProvider {
+        fn provide_credentials(&self) -> CredentialsFuture<'_> {
+            Box::pin(async { Err(anyhow!("no credentials")) })
+        }
+    }
+
+    /// Request credentials `times` times and get the access keys
+    fn get_access_keys<P>(provider: P, times: usize) -> Vec<String>
+    where
+        P: CredentialsProvider + 'static,
+    {
+        wstd::runtime::block_on(async move {
+            let mut access_keys = Vec::new();
+            for _ in 0..times {
+                access_keys.push(provider.provide_credentials().await?.access_key);
+            }
+            Ok::<_, anyhow::Error>(access_keys)
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn cache_credentials_before_expiry() {
+        let (provider, calls) = CountingProvider::new(Some(chrono::Duration::minutes(30)));
+        let access_keys = get_access_keys(CachedCredentialsProvider::new(provider), 3);
+
+        assert_eq!(access_keys, vec!["access_key_1"; 3]);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn cache_credentials_without_expiry() {
+        let (provider, calls) = CountingProvider::new(None);
+        get_access_keys(CachedCredentialsProvider::new(provider), 3);
+
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn refresh_credentials_in_refresh_window() {
+        // Credentials expiring within the 5 minute window are refreshed
+        let (provider, calls) = CountingProvider::new(Some(chrono::Duration::minutes(4)));
+        let access_keys = get_access_keys(CachedCredentialsProvider::new(provider), 3);
+
+        assert_eq!(
+            access_keys,
+            vec!["access_key_1", "access_key_2", "access_key_3"]
+        );
+        assert_eq!(calls.get(), 3);
+
+        let (provider, calls) = CountingProvider::new(Some(chrono::Duration::minutes(4)));
+        let cached =
+            CachedCredentialsProvider::new(provider).refresh_window(Duration::from_secs(60));
+        get_access_keys(cached, 3);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn chain_falls_through() {
+        let (provider, calls) = CountingProvider::new(None);
+        let chain = CredentialsProviderChain::new()
+            .with(FailingProvider)
+            .with(provider);
+
+        assert_eq!(get_access_keys(chain, 1), vec!["access_key_1"]);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn chain_stops_at_first_credentials() {
+        let (first, first_calls) = CountingProvider::new(None);
+        let (second, second_calls) = CountingProvider::new(None);
+        let chain = CredentialsProviderChain::new().with(first).with(second);
+
+        get_access_keys(chain, 1);
+        assert_eq!(first_calls.get(), 1);
+        assert_eq!(second_calls.get(), 0);
+    }
+
+    #[test]
+    fn chain_errors() {
+        let chain = CredentialsProviderChain::new()
+            .with(FailingProvider)
+            .with(FailingProvider);
+        let error = wstd::runtime::block_on(async move {
+            chain.provide_credentials().await.map(|_| ()).unwrap_err()
+        });
+
+        assert_eq!(
+            error.to_string(),
+            "no credentials provider in the chain returned credentials: [no credentials, no credentials]"
+        );
+    }
+}
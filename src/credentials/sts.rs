@@ -0,0 +1,451 @@
+use std::{env, fs, path::PathBuf, time::Duration};
+
+use anyhow::{anyhow, Result};
+use chrono::DateTime;
+use wstd::{
+    http::{body::IncomingBody, Client, IntoBody, Method, Request, Response, Scheme},
+    io::AsyncRead,
+};
+use xml::reader::{EventReader, XmlEvent};
+
+use super::{Credentials, CredentialsFuture, CredentialsProvider};
+use crate::api::{
+    get_scheme_host, parse_xml_string, percent_encode_query, S3RequestBuilder, S3Response,
+    S3ResponseData,
+};
+
+const STS_SERVICE: &str = "sts";
+const STS_VERSION: &str = "2011-06-15";
+const DEFAULT_SESSION_NAME: &str = "s3-wasi-http";
+
+/// The regional STS endpoint, sts.{region}.amazonaws.com
+fn get_sts_endpoint(region: &str) -> String {
+    format!("{STS_SERVICE}.{region}.amazonaws.com")
+}
+
+/// Encode the STS query parameters as a form body
+fn get_form_body(params: &[(&str, &str)]) -> String {
+    params
+        .iter()
+        .map(|(k, v)| format!("{}={}", percent_encode_query(k), percent_encode_query(v)))
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+/// The temporary credentials returned by AssumeRole and AssumeRoleWithWebIdentity
+struct StsCredentials {
+    credentials: Credentials,
+}
+
+impl StsCredentials {
+    async fn from_response(mut response: S3Response<Self>) -> Result<Credentials> {
+        if !response.status().is_success() {
+            let status = response.status();
+            let (_, mut body) = response.into_parts();
+            let mut data = Vec::<u8>::new();
+            body.read_to_end(&mut data).await?;
+            return Err(anyhow!(
+                "STS request failed with status {status}: {}",
+                parse_sts_error(&data)?
+            ));
+        }
+
+        Ok(response.into_response_data().await?.credentials)
+    }
+}
+
+impl S3ResponseData for StsCredentials {
+    async fn parse_body(response: &mut IncomingBody) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut data = Vec::<u8>::new();
+        response.read_to_end(&mut data).await?;
+        Self::parse_xml(&data)
+    }
+}
+
+impl StsCredentials {
+    /// Parse the credentials of an AssumeRole or AssumeRoleWithWebIdentity response
+    fn parse_xml(data: &[u8]) -> Result<Self> {
+        let mut parser = EventReader::new(data);
+
+        let mut access_key = None;
+        let mut secret_key = None;
+        let mut session_token = None;
+        let mut expires_at = None;
+        loop {
+            match parser.next()? {
+                XmlEvent::EndDocument => break,
+
+                XmlEvent::StartElement { name, .. } if name.local_name == "AccessKeyId" => {
+                    access_key = Some(parse_xml_string(&mut parser, "AccessKeyId")?);
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "SecretAccessKey" => {
+                    secret_key = Some(parse_xml_string(&mut parser, "SecretAccessKey")?);
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "SessionToken" => {
+                    session_token = Some(parse_xml_string(&mut parser, "SessionToken")?);
+                }
+                XmlEvent::StartElement { name, .. } if name.local_name == "Expiration" => {
+                    let datetime = DateTime::parse_from_rfc3339(&parse_xml_string(
+                        &mut parser,
+                        "Expiration",
+                    )?)?
+                    .to_utc();
+                    expires_at = Some(datetime);
+                }
+
+                _ => {}
+            }
+        }
+
+        match (access_key, secret_key) {
+            (Some(access_key), Some(secret_key)) => Ok(Self {
+                credentials: Credentials {
+                    access_key,
+                    secret_key,
+                    session_token,
+                    expires_at,
+                },
+            }),
+            _ => Err(anyhow!(
+                "Invalid response object, Credentials has no AccessKeyId or SecretAccessKey"
+            )),
+        }
+    }
+}
+
+/// Get the code and message of an STS error response
+fn parse_sts_error(data: &[u8]) -> Result<String> {
+    let mut parser = EventReader::new(data);
+    let mut code = String::new();
+    let mut message = String::new();
+    loop {
+        match parser.next()? {
+            XmlEvent::EndDocument => break,
+
+            XmlEvent::StartElement { name, .. } if name.local_name == "Code" => {
+                code = parse_xml_string(&mut parser, "Code")?;
+            }
+            XmlEvent::StartElement { name, .. } if name.local_name == "Message" => {
+                message = parse_xml_string(&mut parser, "Message")?;
+            }
+
+            _ => {}
+        }
+    }
+
+    Ok(format!("{code} {message}"))
+}
+
+/// Provides temporary credentials from STS AssumeRoleWithWebIdentity
+///
+/// The web identity token is read from the token file for every request, so
+/// rotated tokens are picked up. Wrap the provider in a
+/// [super::CachedCredentialsProvider] to only call STS when the credentials
+/// are about to expire.
+///
+/// # Examples
+/// ```no_run
+/// # use s3_wasi_http::{S3Client, credentials::{CachedCredentialsProvider, sts::WebIdentityCredentialsProvider}};
+/// # fn example() -> anyhow::Result<()> {
+/// let client = S3Client::new_with_credentials_provider(
+///     CachedCredentialsProvider::new(WebIdentityCredentialsProvider::from_env()?),
+///     "eu-north-1".to_string(),
+///     "mybucket.s3.eu-north-1.amazonaws.com".to_string(),
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub struct WebIdentityCredentialsProvider {
+    client: Client,
+    role_arn: String,
+    token_file: PathBuf,
+    role_session_name: String,
+    duration: Option<Duration>,
+    endpoint: String,
+}
+
+impl WebIdentityCredentialsProvider {
+    /// Assume `role_arn` with the token in `token_file` using the regional
+    /// STS endpoint of `region`
+    pub fn new(role_arn: &str, token_file: PathBuf, region: &str) -> Self {
+        Self {
+            client: Client::new(),
+            role_arn: role_arn.to_owned(),
+            token_file,
+            role_session_name: DEFAULT_SESSION_NAME.to_string(),
+            duration: None,
+            endpoint: get_sts_endpoint(region),
+        }
+    }
+
+    /// Create the provider from AWS_ROLE_ARN, AWS_WEB_IDENTITY_TOKEN_FILE,
+    /// AWS_ROLE_SESSION_NAME and AWS_REGION or AWS_DEFAULT_REGION
+    pub fn from_env() -> Result<Self> {
+        let role_arn =
+            env::var("AWS_ROLE_ARN").map_err(|_| anyhow!("ENV \"AWS_ROLE_ARN\" isn't set"))?;
+        let token_file = env::var("AWS_WEB_IDENTITY_TOKEN_FILE")
+            .map_err(|_| anyhow!("ENV \"AWS_WEB_IDENTITY_TOKEN_FILE\" isn't set"))?;
+        let region = env::var("AWS_REGION")
+            .or_else(|_| env::var("AWS_DEFAULT_REGION"))
+            .map_err(|_| anyhow!("ENV \"AWS_REGION\" isn't set"))?;
+
+        let provider = Self::new(&role_arn, PathBuf::from(token_file), &region);
+        Ok(match env::var("AWS_ROLE_SESSION_NAME") {
+            Ok(name) => provider.role_session_name(&name),
+            Err(_) => provider,
+        })
+    }
+
+    pub fn role_session_name(self, role_session_name: &str) -> Self {
+        Self {
+            role_session_name: role_session_name.to_owned(),
+            ..self
+        }
+    }
+    /// How long the credentials are valid, STS defaults to 1 hour
+    pub fn duration(self, duration: Duration) -> Self {
+        Self {
+            duration: Some(duration),
+            ..self
+        }
+    }
+    /// Override the STS endpoint, e.g. `http://localhost:8080` for a local STS
+    pub fn endpoint(self, endpoint: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_owned(),
+            ..self
+        }
+    }
+
+    async fn assume_role(&self) -> Result<Credentials> {
+        let token = fs::read_to_string(&self.token_file).map_err(|e| {
+            anyhow!(
+                "failed to read web identity token file {}: {e}",
+                self.token_file.display()
+            )
+        })?;
+        let duration = self.duration.map(|d| d.as_secs().to_string());
+
+        let mut params = vec![
+            ("Action", "AssumeRoleWithWebIdentity"),
+            ("Version", STS_VERSION),
+            ("RoleArn", self.role_arn.as_str()),
+            ("RoleSessionName", self.role_session_name.as_str()),
+            ("WebIdentityToken", token.trim()),
+        ];
+        if let Some(duration) = &duration {
+            params.push(("DurationSeconds", duration));
+        }
+
+        // AssumeRoleWithWebIdentity is authenticated by the token and isn't signed
+        let (scheme, host) = get_scheme_host(&self.endpoint, &Scheme::HTTPS)?;
+        let request = Request::builder()
+            .uri(format!("{scheme}://{host}/"))
+            .method(Method::POST)
+            .header("content-type", "application/x-www-form-urlencoded")
+            .body(get_form_body(&params).into_bytes().into_body())?;
+
+        let response: Response<IncomingBody> = self.client.send(request).await?;
+        StsCredentials::from_response(S3Response::from_response(response)?).await
+    }
+}
+
+impl CredentialsProvider for WebIdentityCredentialsProvider {
+    fn provide_credentials(&self) -> CredentialsFuture<'_> {
+        Box::pin(self.assume_role())
+    }
+}
+
+/// Provides temporary credentials from STS AssumeRole
+///
+/// The AssumeRole request is signed with the credentials from the source
+/// provider. Wrap the provider in a [super::CachedCredentialsProvider] to only
+/// call STS when the credentials are about to expire.
+///
+/// # Examples
+/// ```no_run
+/// # use s3_wasi_http::credentials::{CachedCredentialsProvider, EnvCredentialsProvider, sts::AssumeRoleCredentialsProvider};
+/// let provider = CachedCredentialsProvider::new(
+///     AssumeRoleCredentialsProvider::new(
+///         EnvCredentialsProvider,
+///         "arn:aws:iam::123456789012:role/my-role",
+///         "eu-north-1",
+///     )
+///     .external_id("my-external-id"),
+/// );
+/// ```
+pub struct AssumeRoleCredentialsProvider {
+    client: Client,
+    source: Box<dyn CredentialsProvider>,
+    role_arn: String,
+    role_session_name: String,
+    external_id: Option<String>,
+    duration: Option<Duration>,
+    region: String,
+    endpoint: String,
+}
+
+impl AssumeRoleCredentialsProvider {
+    /// Assume `role_arn` with the credentials from `source` using the
+    /// regional STS endpoint of `region`
+    pub fn new<P>(source: P, role_arn: &str, region: &str) -> Self
+    where
+        P: CredentialsProvider + 'static,
+    {
+        Self {
+            client: Client::new(),
+            source: Box::new(source),
+            role_arn: role_arn.to_owned(),
+            role_session_name: DEFAULT_SESSION_NAME.to_string(),
+            external_id: None,
+            duration: None,
+            region: region.to_owned(),
+            endpoint: get_sts_endpoint(region),
+        }
+    }
+
+    pub fn role_session_name(self, role_session_name: &str) -> Self {
+        Self {
+            role_session_name: role_session_name.to_owned(),
+            ..self
+        }
+    }
+    pub fn external_id(self, external_id: &str) -> Self {
+        Self {
+            external_id: Some(external_id.to_owned()),
+            ..self
+        }
+    }
+    /// How long the credentials are valid, STS defaults to 1 hour
+    pub fn duration(self, duration: Duration) -> Self {
+        Self {
+            duration: Some(duration),
+            ..self
+        }
+    }
+    /// Override the STS endpoint, e.g. `http://localhost:8080` for a local STS
+    pub fn endpoint(self, endpoint: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_owned(),
+            ..self
+        }
+    }
+
+    async fn assume_role(&self) -> Result<Credentials> {
+        let source = self.source.provide_credentials().await?;
+        let duration = self.duration.map(|d| d.as_secs().to_string());
+
+        let mut params = vec![
+            ("Action", "AssumeRole"),
+            ("Version", STS_VERSION),
+            ("RoleArn", self.role_arn.as_str()),
+            ("RoleSessionName", self.role_session_name.as_str()),
+        ];
+        if let Some(external_id) = &self.external_id {
+            params.push(("ExternalId", external_id));
+        }
+        if let Some(duration) = &duration {
+            params.push(("DurationSeconds", duration));
+        }
+
+        let mut builder = S3RequestBuilder::<StsCredentials>::new(
+            Method::POST,
+            "",
+            &source.access_key,
+            &source.secret_key,
+            &self.region,
+            &self.endpoint,
+        );
+        builder
+            .service(STS_SERVICE)
+            .header("content-type", "application/x-www-form-urlencoded")
+            .body(get_form_body(&params));
+        builder.session_token = source.session_token;
+
+        let response = self.client.send(builder.build()?.request).await?;
+        StsCredentials::from_response(S3Response::from_response(response)?).await
+    }
+}
+
+impl CredentialsProvider for AssumeRoleCredentialsProvider {
+    fn provide_credentials(&self) -> CredentialsFuture<'_> {
+        Box::pin(self.assume_role())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WEB_IDENTITY_RESPONSE: &str = r#"<AssumeRoleWithWebIdentityResponse xmlns="https://sts.amazonaws.com/doc/2011-06-15/">
+  <AssumeRoleWithWebIdentityResult>
+    <SubjectFromWebIdentityToken>amzn1.account.AF6RHO7KZU5XRVQJGXK6HB56KR2A</SubjectFromWebIdentityToken>
+    <Audience>client.5498841531868486423.1548@apps.example.com</Audience>
+    <AssumedRoleUser>
+      <Arn>arn:aws:sts::123456789012:assumed-role/FederatedWebIdentityRole/app1</Arn>
+      <AssumedRoleId>AROACLKWSDQRAOEXAMPLE:app1</AssumedRoleId>
+    </AssumedRoleUser>
+    <Credentials>
+      <SessionToken>AQoDYXdzEE0a8ANXXXXXXXXNO1ewxE5TijQyp+IEXAMPLE</SessionToken>
+      <SecretAccessKey>wJalrXUtnFEMI/K7MDENG/bPxRfiCYzEXAMPLEKEY</SecretAccessKey>
+      <Expiration>2014-10-24T23:00:23Z</Expiration>
+      <AccessKeyId>ASgeIAIOSFODNN7EXAMPLE</AccessKeyId>
+    </Credentials>
+    <SourceIdentity>SourceIdentityValue</SourceIdentity>
+    <Provider>www.amazon.com</Provider>
+  </AssumeRoleWithWebIdentityResult>
+  <ResponseMetadata>
+    <RequestId>ad4156e9-bce1-11e2-82e6-6b6efEXAMPLE</RequestId>
+  </ResponseMetadata>
+</AssumeRoleWithWebIdentityResponse>"#;
+
+    const ERROR_RESPONSE: &str = r#"<ErrorResponse xmlns="https://sts.amazonaws.com/doc/2011-06-15/">
+  <Error>
+    <Type>Sender</Type>
+    <Code>InvalidIdentityToken</Code>
+    <Message>No OpenIDConnect provider found in your account for https://example.com</Message>
+  </Error>
+  <RequestId>c6104cbe-af31-11e0-8154-cbc7ccf896c7</RequestId>
+</ErrorResponse>"#;
+
+    #[test]
+    fn parse_web_identity_response() {
+        let credentials = StsCredentials::parse_xml(WEB_IDENTITY_RESPONSE.as_bytes())
+            .unwrap()
+            .credentials;
+
+        assert_eq!(credentials.access_key, "ASgeIAIOSFODNN7EXAMPLE");
+        assert_eq!(
+            credentials.secret_key,
+            "wJalrXUtnFEMI/K7MDENG/bPxRfiCYzEXAMPLEKEY"
+        );
+        assert_eq!(
+            credentials.session_token.as_deref(),
+            Some("AQoDYXdzEE0a8ANXXXXXXXXNO1ewxE5TijQyp+IEXAMPLE")
+        );
+        assert_eq!(
+            credentials.expires_at,
+            Some(
+                DateTime::parse_from_rfc3339("2014-10-24T23:00:23Z")
+                    .unwrap()
+                    .to_utc()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_response_without_credentials() {
+        assert!(StsCredentials::parse_xml(ERROR_RESPONSE.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn parse_error_response() {
+        assert_eq!(
+            parse_sts_error(ERROR_RESPONSE.as_bytes()).unwrap(),
+            "InvalidIdentityToken No OpenIDConnect provider found in your account for https://example.com"
+        );
+    }
+}
@@ -0,0 +1,488 @@
+use std::{net::IpAddr, str::FromStr};
+
+use anyhow::{anyhow, Result};
+use wstd::http::{Scheme, Uri};
+
+use crate::{
+    api::{get_scheme_host_port, with_port},
+    arn::{is_arn, S3Arn},
+};
+
+/// How the bucket is addressed in the request url
+#[derive(Clone, Default)]
+pub enum AddressingStyle {
+    /// `{bucket}.{endpoint}/{key}`
+    VirtualHosted,
+    /// `{endpoint}/{bucket}/{key}`, used by MinIO, Ceph and other S3
+    /// compatible servers
+    Path,
+    /// Virtual hosted if the bucket name is a valid host name label and the
+    /// endpoint isn't an ip address or localhost, path style otherwise
+    #[default]
+    Auto,
+}
+
+/// Bucket name prefixes reserved by S3
+const RESERVED_BUCKET_PREFIXES: [&str; 3] = ["xn--", "sthree-", "amzn-s3-demo-"];
+/// Bucket name suffixes reserved by S3 for access point aliases
+const RESERVED_BUCKET_SUFFIXES: [&str; 2] = ["-s3alias", "--ol-s3"];
+
+/// Validate a bucket name against the S3 general purpose bucket naming rules
+///
+/// Names are 3 to 63 lowercase letters, digits, dots and hyphens, start and
+/// end with a letter or digit and aren't formatted as an ip address. Legacy
+/// buckets with uppercase letters or underscores can only be used with
+/// [AddressingStyle::Path].
+pub fn validate_bucket_name(bucket: &str) -> Result<()> {
+    if !(3..=63).contains(&bucket.len()) {
+        return Err(anyhow!(
+            "bucket name \"{bucket}\" has to be between 3 and 63 characters long"
+        ));
+    }
+    if let Some(c) = bucket
+        .chars()
+        .find(|c| !(c.is_ascii_lowercase() || c.is_ascii_digit() || *c == '-' || *c == '.'))
+    {
+        return Err(anyhow!(
+            "bucket name \"{bucket}\" can only contain lowercase letters, digits, dots and hyphens, found {c:?}"
+        ));
+    }
+    if !bucket.starts_with(|c: char| c.is_ascii_alphanumeric())
+        || !bucket.ends_with(|c: char| c.is_ascii_alphanumeric())
+    {
+        return Err(anyhow!(
+            "bucket name \"{bucket}\" has to start and end with a letter or digit"
+        ));
+    }
+    if bucket.contains("..") {
+        return Err(anyhow!(
+            "bucket name \"{bucket}\" can't contain adjacent dots"
+        ));
+    }
+    if bucket.parse::<IpAddr>().is_ok() {
+        return Err(anyhow!(
+            "bucket name \"{bucket}\" can't be formatted as an ip address"
+        ));
+    }
+    if let Some(prefix) = RESERVED_BUCKET_PREFIXES
+        .iter()
+        .find(|prefix| bucket.starts_with(*prefix))
+    {
+        return Err(anyhow!(
+            "bucket name \"{bucket}\" can't start with the reserved prefix \"{prefix}\""
+        ));
+    }
+    if let Some(suffix) = RESERVED_BUCKET_SUFFIXES
+        .iter()
+        .find(|suffix| bucket.ends_with(*suffix))
+    {
+        return Err(anyhow!(
+            "bucket name \"{bucket}\" can't end with the reserved suffix \"{suffix}\""
+        ));
+    }
+
+    Ok(())
+}
+
+/// Check if the bucket can be used as a label in the host name
+///
+/// Buckets with dots are only used over http since they don't match the
+/// wildcard TLS certificate of the endpoint.
+pub(crate) fn is_virtual_host_compatible(bucket: &str, scheme: &str) -> bool {
+    validate_bucket_name(bucket).is_ok() && (scheme == "http" || !bucket.contains('.'))
+}
+
+/// Check if the host is an ip address or localhost, they can't have a bucket
+/// sub domain
+fn is_ip_or_localhost(host: &str) -> bool {
+    host == "localhost"
+        || host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .is_ok()
+}
+
+/// Get the scheme, host and path prefix of a request to `bucket` on `endpoint`
+///
+/// Without a bucket `endpoint` is a bucket endpoint and is used as is. An ARN
+/// bucket is sent to the host of the access point, using its FIPS or
+/// dual-stack variant if `endpoint` is one. The path of `endpoint`, e.g. of a
+/// reverse proxy, is kept in front of the bucket.
+pub(crate) fn get_bucket_scheme_host_path(
+    endpoint: &str,
+    scheme: &Scheme,
+    bucket: Option<&str>,
+    addressing_style: &AddressingStyle,
+) -> Result<(String, String, String)> {
+    let (scheme, host, port) = get_scheme_host_port(endpoint, scheme)?;
+    let path = Uri::from_str(endpoint)?
+        .path()
+        .trim_end_matches('/')
+        .to_owned();
+    let Some(bucket) = bucket else {
+        return Ok((scheme, with_port(host, port), path));
+    };
+
+    if is_arn(bucket) {
+        let labels = host.split('.').collect::<Vec<&str>>();
+        let fips = labels.iter().any(|label| label.ends_with("-fips"));
+        let dual_stack = labels.contains(&"dualstack");
+        let host = S3Arn::parse(bucket)?.resolve_endpoint(fips, dual_stack)?;
+        return Ok((scheme, host, String::new()));
+    }
+
+    let virtual_hosted = match addressing_style {
+        AddressingStyle::VirtualHosted => true,
+        AddressingStyle::Path => false,
+        AddressingStyle::Auto => {
+            is_virtual_host_compatible(bucket, &scheme) && !is_ip_or_localhost(&host)
+        }
+    };
+
+    match virtual_hosted {
+        true => Ok((scheme, with_port(format!("{bucket}.{host}"), port), path)),
+        false => Ok((scheme, with_port(host, port), format!("{path}/{bucket}"))),
+    }
+}
+
+/// The DNS suffix of the partition of a region
+fn get_dns_suffix(region: &str) -> &'static str {
+    match region.starts_with("cn-") {
+        true => "amazonaws.com.cn",
+        false => "amazonaws.com",
+    }
+}
+
+/// Resolves the AWS S3 endpoint of a region
+///
+/// Supports the standard, China (`amazonaws.com.cn`) and GovCloud
+/// partitions, the FIPS (`s3-fips`) and dual-stack (IPv4 and IPv6) variants
+/// and Transfer Acceleration (`s3-accelerate`).
+///
+/// # Examples
+/// ```
+/// # use s3_wasi_http::endpoint::EndpointResolver;
+/// let resolver = EndpointResolver::new("us-gov-west-1")
+///     .fips(true)
+///     .dual_stack(true);
+/// assert_eq!(
+///     resolver.resolve_bucket_endpoint("mybucket")?,
+///     "mybucket.s3-fips.dualstack.us-gov-west-1.amazonaws.com"
+/// );
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct EndpointResolver {
+    region: String,
+    fips: bool,
+    dual_stack: bool,
+    accelerate: bool,
+}
+
+impl EndpointResolver {
+    pub fn new(region: &str) -> Self {
+        Self {
+            region: region.to_owned(),
+            fips: false,
+            dual_stack: false,
+            accelerate: false,
+        }
+    }
+
+    /// Use the FIPS 140 validated endpoint, not available in China
+    pub fn fips(mut self, fips: bool) -> Self {
+        self.fips = fips;
+        self
+    }
+
+    /// Use the dual-stack endpoint reachable over IPv4 and IPv6
+    pub fn dual_stack(mut self, dual_stack: bool) -> Self {
+        self.dual_stack = dual_stack;
+        self
+    }
+
+    /// Use Transfer Acceleration, the bucket has to have it enabled
+    ///
+    /// Only available in the standard partition, not with FIPS and the
+    /// bucket name can't contain dots.
+    pub fn accelerate(mut self, accelerate: bool) -> Self {
+        self.accelerate = accelerate;
+        self
+    }
+
+    pub fn region(&self) -> &str {
+        &self.region
+    }
+
+    /// Get the service endpoint, e.g. `s3.eu-north-1.amazonaws.com`
+    ///
+    /// Transfer Acceleration has no service endpoint for requests without a
+    /// bucket, the endpoint can only be used with virtual hosted buckets.
+    pub fn resolve_service_endpoint(&self) -> Result<String> {
+        let region = &self.region;
+        if region.is_empty() {
+            return Err(anyhow!("can't resolve the endpoint without a region"));
+        }
+        let is_china = region.starts_with("cn-");
+        if self.fips && is_china {
+            return Err(anyhow!("FIPS endpoints aren't available in {region}"));
+        }
+
+        let dual_stack = match self.dual_stack {
+            true => ".dualstack",
+            false => "",
+        };
+        if self.accelerate {
+            if self.fips {
+                return Err(anyhow!(
+                    "Transfer Acceleration doesn't support FIPS endpoints"
+                ));
+            }
+            if is_china || region.starts_with("us-gov-") {
+                return Err(anyhow!("Transfer Acceleration isn't available in {region}"));
+            }
+            return Ok(format!("s3-accelerate{dual_stack}.amazonaws.com"));
+        }
+
+        let service = match self.fips {
+            true => "s3-fips",
+            false => "s3",
+        };
+        Ok(format!(
+            "{service}{dual_stack}.{region}.{}",
+            get_dns_suffix(region)
+        ))
+    }
+
+    /// Get the virtual hosted endpoint of `bucket`, e.g.
+    /// `mybucket.s3.eu-north-1.amazonaws.com`
+    ///
+    /// Returns an error if the bucket name is invalid or can't be used in the
+    /// host name, use [AddressingStyle::Path] with the service endpoint for
+    /// those buckets. The bucket can be an access point ARN, see [S3Arn].
+    pub fn resolve_bucket_endpoint(&self, bucket: &str) -> Result<String> {
+        if is_arn(bucket) {
+            if self.accelerate {
+                return Err(anyhow!(
+                    "Transfer Acceleration can't be used with access points"
+                ));
+            }
+            return S3Arn::parse(bucket)?.resolve_endpoint(self.fips, self.dual_stack);
+        }
+
+        validate_bucket_name(bucket)?;
+        if bucket.contains('.') {
+            return Err(match self.accelerate {
+                true => anyhow!(
+                    "bucket name \"{bucket}\" can't contain dots with Transfer Acceleration"
+                ),
+                false => anyhow!(
+                    "bucket name \"{bucket}\" contains dots, which don't match the TLS certificate of virtual hosted endpoints, use path style addressing"
+                ),
+            });
+        }
+
+        Ok(format!("{bucket}.{}", self.resolve_service_endpoint()?))
+    }
+}
+
+/// The default bucket endpoint of a region, without validating the bucket
+pub(crate) fn get_default_bucket_endpoint(bucket: &str, region: &str) -> String {
+    format!("{bucket}.s3.{region}.{}", get_dns_suffix(region))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_url(endpoint: &str, bucket: Option<&str>, addressing_style: AddressingStyle) -> String {
+        let (scheme, host, path) =
+            get_bucket_scheme_host_path(endpoint, &Scheme::HTTPS, bucket, &addressing_style)
+                .unwrap();
+        format!("{scheme}://{host}{path}")
+    }
+
+    #[test]
+    fn custom_endpoints() {
+        assert_eq!(
+            get_url(
+                "http://localhost:9000",
+                Some("mybucket"),
+                AddressingStyle::Auto
+            ),
+            "http://localhost:9000/mybucket"
+        );
+        assert_eq!(
+            get_url("s3.example.com", Some("mybucket"), AddressingStyle::Auto),
+            "https://mybucket.s3.example.com"
+        );
+        assert_eq!(
+            get_url(
+                "https://proxy.example.com/s3/",
+                Some("mybucket"),
+                AddressingStyle::Path
+            ),
+            "https://proxy.example.com/s3/mybucket"
+        );
+        assert_eq!(
+            get_url("https://proxy.example.com/s3", None, AddressingStyle::Auto),
+            "https://proxy.example.com/s3"
+        );
+    }
+
+    #[test]
+    fn service_endpoints() {
+        let resolve = |resolver: EndpointResolver| resolver.resolve_service_endpoint().unwrap();
+
+        assert_eq!(
+            resolve(EndpointResolver::new("eu-north-1")),
+            "s3.eu-north-1.amazonaws.com"
+        );
+        assert_eq!(
+            resolve(EndpointResolver::new("us-east-1").fips(true)),
+            "s3-fips.us-east-1.amazonaws.com"
+        );
+        assert_eq!(
+            resolve(EndpointResolver::new("eu-north-1").dual_stack(true)),
+            "s3.dualstack.eu-north-1.amazonaws.com"
+        );
+        assert_eq!(
+            resolve(
+                EndpointResolver::new("us-east-1")
+                    .fips(true)
+                    .dual_stack(true)
+            ),
+            "s3-fips.dualstack.us-east-1.amazonaws.com"
+        );
+        assert_eq!(
+            resolve(EndpointResolver::new("eu-north-1").accelerate(true)),
+            "s3-accelerate.amazonaws.com"
+        );
+        assert_eq!(
+            resolve(
+                EndpointResolver::new("eu-north-1")
+                    .accelerate(true)
+                    .dual_stack(true)
+            ),
+            "s3-accelerate.dualstack.amazonaws.com"
+        );
+        assert_eq!(
+            resolve(EndpointResolver::new("us-gov-west-1").fips(true)),
+            "s3-fips.us-gov-west-1.amazonaws.com"
+        );
+        assert_eq!(
+            resolve(EndpointResolver::new("cn-north-1")),
+            "s3.cn-north-1.amazonaws.com.cn"
+        );
+        assert_eq!(
+            resolve(EndpointResolver::new("cn-northwest-1").dual_stack(true)),
+            "s3.dualstack.cn-northwest-1.amazonaws.com.cn"
+        );
+    }
+
+    #[test]
+    fn unavailable_service_endpoints() {
+        let resolvers = [
+            EndpointResolver::new(""),
+            EndpointResolver::new("cn-north-1").fips(true),
+            EndpointResolver::new("us-east-1")
+                .accelerate(true)
+                .fips(true),
+            EndpointResolver::new("us-gov-west-1").accelerate(true),
+            EndpointResolver::new("cn-north-1").accelerate(true),
+        ];
+        for resolver in resolvers {
+            assert!(resolver.resolve_service_endpoint().is_err());
+        }
+    }
+
+    #[test]
+    fn bucket_endpoints() {
+        assert_eq!(
+            EndpointResolver::new("eu-north-1")
+                .resolve_bucket_endpoint("mybucket")
+                .unwrap(),
+            "mybucket.s3.eu-north-1.amazonaws.com"
+        );
+        assert_eq!(
+            EndpointResolver::new("eu-north-1")
+                .accelerate(true)
+                .resolve_bucket_endpoint("mybucket")
+                .unwrap(),
+            "mybucket.s3-accelerate.amazonaws.com"
+        );
+        assert_eq!(
+            EndpointResolver::new("us-east-1")
+                .fips(true)
+                .resolve_bucket_endpoint(
+                    "arn:aws:s3:us-east-1:123456789012:accesspoint/my-access-point"
+                )
+                .unwrap(),
+            "my-access-point-123456789012.s3-accesspoint-fips.us-east-1.amazonaws.com"
+        );
+
+        // Dots don't match the wildcard TLS certificate of the bucket host
+        let resolver = EndpointResolver::new("eu-north-1");
+        assert!(resolver.resolve_bucket_endpoint("my.bucket").is_err());
+        assert!(resolver
+            .accelerate(true)
+            .resolve_bucket_endpoint("my.bucket")
+            .is_err());
+        assert!(EndpointResolver::new("us-east-1")
+            .accelerate(true)
+            .resolve_bucket_endpoint(
+                "arn:aws:s3:us-east-1:123456789012:accesspoint/my-access-point"
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn valid_bucket_names() {
+        for bucket in ["abc", "my-bucket", "my.bucket.1", &"a".repeat(63)] {
+            assert!(validate_bucket_name(bucket).is_ok(), "{bucket}");
+        }
+    }
+
+    #[test]
+    fn invalid_bucket_names() {
+        let buckets = [
+            "ab".to_string(),
+            "a".repeat(64),
+            "MyBucket".to_string(),
+            "my_bucket".to_string(),
+            "-mybucket".to_string(),
+            "mybucket.".to_string(),
+            "my..bucket".to_string(),
+            "192.168.5.4".to_string(),
+            "xn--mybucket".to_string(),
+            "mybucket-s3alias".to_string(),
+            "mybucket--ol-s3".to_string(),
+        ];
+        for bucket in buckets {
+            assert!(validate_bucket_name(&bucket).is_err(), "{bucket}");
+        }
+    }
+
+    #[test]
+    fn virtual_host_compatible() {
+        assert!(is_virtual_host_compatible("mybucket", "https"));
+        assert!(is_virtual_host_compatible("my.bucket", "http"));
+        assert!(!is_virtual_host_compatible("my.bucket", "https"));
+        assert!(!is_virtual_host_compatible("My_Bucket", "http"));
+
+        // Buckets that can't be a host label fall back to path style
+        assert_eq!(
+            get_url(
+                "s3.eu-north-1.amazonaws.com",
+                Some("my.bucket"),
+                AddressingStyle::Auto
+            ),
+            "https://s3.eu-north-1.amazonaws.com/my.bucket"
+        );
+        assert_eq!(
+            get_url("http://[::1]:9000", Some("mybucket"), AddressingStyle::Auto),
+            "http://[::1]:9000/mybucket"
+        );
+    }
+}
@@ -1,47 +1,88 @@
-use std::env;
+use std::{env, time::Duration};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use api::{
+    abort_multipart_upload::{AbortMultipartUploadRequest, AbortMultipartUploadResponse},
+    aws_chunked::DEFAULT_CHUNK_SIZE,
+    complete_multipart_upload::{CompleteMultipartUploadRequest, CompleteMultipartUploadResponse},
+    copy_object::{CopyObjectRequest, CopyObjectResponse},
+    create_multipart_upload::{CreateMultipartUploadRequest, CreateMultipartUploadResponse},
+    delete_object::{DeleteObjectRequest, DeleteObjectResponse},
+    delete_objects::{DeleteObjectsRequest, DeleteObjectsResponse},
     get_object::{GetObjectRequest, GetObjectResponse},
     head_object::{HeadObjectRequest, HeadObjectResponse},
     list_buckets::{ListBucketsRequest, ListBucketsResponse},
+    list_multipart_uploads::{ListMultipartUploadsRequest, ListMultipartUploadsResponse},
     list_objects_v2::{ListObjectsV2Request, ListObjectsV2Response},
+    list_parts::{ListPartsRequest, ListPartsResponse},
+    post_policy::{PostPolicy, PostPolicyForm},
     put_object::{PutObjectRequest, PutObjectResponse},
-    S3Request, S3RequestBuilder, S3RequestData, S3Response, S3ResponseData,
+    upload_part::{UploadPartRequest, UploadPartResponse},
+    upload_part_copy::{UploadPartCopyRequest, UploadPartCopyResponse},
+    ChecksumAlgorithm, S3Request, S3RequestBuilder, S3RequestData, S3Response, S3ResponseData,
+    S3StreamedRequest,
+};
+use arn::is_arn;
+use credentials::{
+    profile::{Profile, ProfileCredentialsProvider},
+    Credentials, CredentialsProvider, StaticCredentialsProvider,
+};
+use endpoint::{
+    get_bucket_scheme_host_path, get_default_bucket_endpoint, AddressingStyle, EndpointResolver,
+};
+use retry::{get_retry_after, is_retryable_status, RetryPolicy, RetryReason, RetryTokenBucket};
+use wstd::{
+    http::{Client, Scheme},
+    io::AsyncRead,
 };
-use wstd::http::Client;
 
 pub mod api;
+pub mod arn;
+pub mod credentials;
+pub mod endpoint;
+pub mod retry;
 
 const AWS_SERVICE: &str = "s3";
 
 /// The S3Client
 pub struct S3Client {
     client: Client,
-    access_key: String,
-    secret_key: String,
+    credentials: Box<dyn CredentialsProvider>,
     region: String,
 
     endpoint: String,
+    bucket: Option<String>,
+    addressing_style: AddressingStyle,
+
+    retry_policy: RetryPolicy,
+    retry_tokens: RetryTokenBucket,
 }
 
 impl S3Client {
     /// Create a new s3 client
     ///
     /// Uses region and bucket to create a s3 endpoint in the format
-    /// {bucket}.s3.{region}.amazonaws.com
+    /// {bucket}.s3.{region}.amazonaws.com, or amazonaws.com.cn in China
     ///
     /// see [`S3Client::set_endpoint`] to override the endpoint created from
-    /// the region and bucket or [`S3Client::new_client`] to set the endpoint.
+    /// the region and bucket, [`S3Client::set_endpoint_resolver`] for FIPS,
+    /// dual-stack and accelerated endpoints or [`S3Client::new_client`] to
+    /// set the endpoint.
     pub fn new(access_key: String, secret_key: String, region: String, bucket: String) -> Self {
-        let endpoint = format!("{}.{}.{}.amazonaws.com", bucket, AWS_SERVICE, region);
+        let endpoint = get_default_bucket_endpoint(&bucket, &region);
 
         Self {
             client: Client::new(),
-            access_key,
-            secret_key,
+            credentials: Box::new(StaticCredentialsProvider::new(Credentials::new(
+                &access_key,
+                &secret_key,
+            ))),
             region,
             endpoint,
+            bucket: None,
+            addressing_style: AddressingStyle::default(),
+            retry_policy: RetryPolicy::default(),
+            retry_tokens: RetryTokenBucket::default(),
         }
     }
 
@@ -50,6 +91,9 @@ impl S3Client {
     /// Endpoint is expected to be an s3 compatible bucket endpoint.
     /// for aws the format is {bucket}.s3.{region}.amazonaws.com, see [`S3Client::new`]
     /// for a nicer setup for aws.r
+    ///
+    /// The endpoint can have a scheme and a port, e.g. `http://localhost:9000`
+    /// or `http://[::1]:9000` for a local server, https is used without a scheme.
     pub fn new_client(
         access_key: String,
         secret_key: String,
@@ -58,16 +102,54 @@ impl S3Client {
     ) -> Self {
         Self {
             client: Client::new(),
-            access_key,
-            secret_key,
+            credentials: Box::new(StaticCredentialsProvider::new(Credentials::new(
+                &access_key,
+                &secret_key,
+            ))),
             region,
             endpoint,
+            bucket: None,
+            addressing_style: AddressingStyle::default(),
+            retry_policy: RetryPolicy::default(),
+            retry_tokens: RetryTokenBucket::default(),
+        }
+    }
+
+    /// Create a new s3 client with a credentials provider
+    ///
+    /// The provider is asked for credentials every time a request is created,
+    /// see [credentials::CachedCredentialsProvider] and
+    /// [credentials::CredentialsProviderChain].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use s3_wasi_http::{S3Client, credentials::{CachedCredentialsProvider, EnvCredentialsProvider}};
+    /// let client = S3Client::new_with_credentials_provider(
+    ///     CachedCredentialsProvider::new(EnvCredentialsProvider),
+    ///     "eu-north-1".to_string(),
+    ///     "mybucket.s3.eu-north-1.amazonaws.com".to_string(),
+    /// );
+    /// ```
+    pub fn new_with_credentials_provider<P>(provider: P, region: String, endpoint: String) -> Self
+    where
+        P: CredentialsProvider + 'static,
+    {
+        Self {
+            client: Client::new(),
+            credentials: Box::new(provider),
+            region,
+            endpoint,
+            bucket: None,
+            addressing_style: AddressingStyle::default(),
+            retry_policy: RetryPolicy::default(),
+            retry_tokens: RetryTokenBucket::default(),
         }
     }
 
     /// Create a new s3 client from envs
     ///
     /// Use [S3Client::new_secrets_from_endpoint] to set bucket and region in code.
+    /// AWS_SESSION_TOKEN is used if set.
     ///
     /// <div class="warning">
     /// Panics if AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY, AWS_DEFAULT_REGION and AWS_ENDPOINT_URL_S3 isn't set.
@@ -79,20 +161,29 @@ impl S3Client {
         let region = env::var("AWS_DEFAULT_REGION").expect("ENV \"AWS_DEFAULT_REGION\" isn't set");
         let endpoint =
             env::var("AWS_ENDPOINT_URL_S3").expect("ENV \"AWS_ENDPOINT_URL_S3\" isn't set");
+        let session_token = env::var("AWS_SESSION_TOKEN").ok();
 
         Self {
             client: Client::new(),
-            access_key,
-            secret_key,
+            credentials: Box::new(StaticCredentialsProvider::new(Credentials {
+                access_key,
+                secret_key,
+                session_token,
+                expires_at: None,
+            })),
             region,
             endpoint,
+            bucket: None,
+            addressing_style: AddressingStyle::default(),
+            retry_policy: RetryPolicy::default(),
+            retry_tokens: RetryTokenBucket::default(),
         }
     }
 
     /// Create a new s3 client from envs and the endpoint from args
     ///
     /// Uses region and bucket to create a s3 endpoint in the format
-    /// {bucket}.s3.{region}.amazonaws.com
+    /// {bucket}.s3.{region}.amazonaws.com, AWS_SESSION_TOKEN is used if set.
     ///
     /// <div class="warning">
     /// Panics if AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY isn't set.
@@ -101,29 +192,168 @@ impl S3Client {
         let access_key = env::var("AWS_ACCESS_KEY_ID").expect("ENV \"AWS_ACCESS_KEY\" isn't set");
         let secret_key =
             env::var("AWS_SECRET_ACCESS_KEY").expect("ENV \"AWS_SECRET_ACCESS_KEY\" isn't set");
-        let endpoint = format!("{}.{}.{}.amazonaws.com", bucket, AWS_SERVICE, region);
+        let endpoint = get_default_bucket_endpoint(&bucket, &region);
+        let session_token = env::var("AWS_SESSION_TOKEN").ok();
 
         Self {
             client: Client::new(),
-            access_key,
-            secret_key,
+            credentials: Box::new(StaticCredentialsProvider::new(Credentials {
+                access_key,
+                secret_key,
+                session_token,
+                expires_at: None,
+            })),
             region,
             endpoint,
+            bucket: None,
+            addressing_style: AddressingStyle::default(),
+            retry_policy: RetryPolicy::default(),
+            retry_tokens: RetryTokenBucket::default(),
         }
     }
 
+    /// Create a new s3 client from a profile in the shared AWS config files
+    ///
+    /// Uses the profile `profile` or AWS_PROFILE if `None`, see
+    /// [credentials::profile::Profile]. With an s3 `endpoint_url` in the
+    /// profile the endpoint is used as a service endpoint with
+    /// [S3Client::set_bucket], so the bucket is addressed according to
+    /// [S3Client::set_addressing_style], otherwise the bucket endpoint is
+    /// {bucket}.s3.{region}.amazonaws.com.
+    ///
+    /// The credentials are read from the files for every request so rotated
+    /// credentials are picked up.
+    pub fn new_from_profile(profile: Option<&str>, bucket: String) -> Result<Self> {
+        let profile = Profile::load(profile)?;
+        let region = profile
+            .region
+            .ok_or_else(|| anyhow!("profile \"{}\" has no region", profile.name))?;
+        let (endpoint, bucket) = match profile.endpoint_url {
+            Some(endpoint_url) => (endpoint_url, Some(bucket)),
+            None => (get_default_bucket_endpoint(&bucket, &region), None),
+        };
+
+        Ok(Self {
+            client: Client::new(),
+            credentials: Box::new(ProfileCredentialsProvider::new().profile(&profile.name)),
+            region,
+            endpoint,
+            bucket,
+            addressing_style: AddressingStyle::default(),
+            retry_policy: RetryPolicy::default(),
+            retry_tokens: RetryTokenBucket::default(),
+        })
+    }
+
     /// Set the bucket endpoint to use
     pub fn set_endpoint(&mut self, endpoint: String) {
         self.endpoint = endpoint;
     }
 
+    /// Set the region and endpoint from an endpoint resolver
+    ///
+    /// Uses the endpoint of `bucket`, or the service endpoint without a
+    /// bucket for clients using [S3Client::set_bucket] or [S3Client::send_to_bucket].
+    /// An ARN bucket is set with [S3Client::set_bucket] so requests are sent
+    /// to the access point and signed for the region and service of the ARN.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use s3_wasi_http::{S3Client, endpoint::EndpointResolver};
+    /// let mut client = S3Client::new_from_env();
+    /// client.set_endpoint_resolver(
+    ///     EndpointResolver::new("us-east-1")
+    ///         .fips(true)
+    ///         .dual_stack(true),
+    ///     Some("mybucket"),
+    /// )?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn set_endpoint_resolver(
+        &mut self,
+        resolver: EndpointResolver,
+        bucket: Option<&str>,
+    ) -> Result<()> {
+        self.endpoint = match bucket {
+            Some(bucket) if is_arn(bucket) => {
+                // Fails for access points without the FIPS or dual-stack variant
+                resolver.resolve_bucket_endpoint(bucket)?;
+                self.bucket = Some(bucket.to_owned());
+                resolver.resolve_service_endpoint()?
+            }
+            Some(bucket) => resolver.resolve_bucket_endpoint(bucket)?,
+            None => resolver.resolve_service_endpoint()?,
+        };
+        self.region = resolver.region().to_owned();
+
+        Ok(())
+    }
+
     /// Get the bucket endpoint in use
     pub fn endpoint(&self) -> &String {
         &self.endpoint
     }
 
+    /// Send requests to `bucket`, the endpoint is then a service endpoint
+    ///
+    /// With a bucket the endpoint is used without a bucket, e.g.
+    /// `s3.eu-north-1.amazonaws.com` or `http://localhost:9000`, and the
+    /// bucket is added to the host or path, see [S3Client::set_addressing_style].
+    /// The bucket can also be an access point, Object Lambda or Outposts ARN,
+    /// see [arn::S3Arn].
+    /// Use [S3Client::send_to_bucket] to send single requests to other buckets.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use s3_wasi_http::{S3Client, endpoint::AddressingStyle};
+    /// let mut client = S3Client::new_client(
+    ///     "minioadmin".to_string(),
+    ///     "minioadmin".to_string(),
+    ///     "us-east-1".to_string(),
+    ///     "http://localhost".to_string(),
+    /// );
+    /// client.set_bucket(Some("mybucket".to_string()));
+    /// client.set_addressing_style(AddressingStyle::Path);
+    /// ```
+    pub fn set_bucket(&mut self, bucket: Option<String>) {
+        self.bucket = bucket;
+    }
+
+    /// Get the bucket requests are sent to, `None` if the endpoint is a
+    /// bucket endpoint
+    pub fn bucket(&self) -> Option<&str> {
+        self.bucket.as_deref()
+    }
+
+    /// Set how the bucket is addressed, defaults to [endpoint::AddressingStyle::Auto]
+    ///
+    /// Only used with [S3Client::set_bucket] or [S3Client::send_to_bucket].
+    pub fn set_addressing_style(&mut self, addressing_style: AddressingStyle) {
+        self.addressing_style = addressing_style;
+    }
+
+    /// Set how failed requests are retried
+    ///
+    /// see [retry::RetryPolicy], use [retry::RetryPolicy::disabled] to never retry
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
+    /// Set the credentials provider used to sign requests
+    ///
+    /// see [credentials::CredentialsProvider]
+    pub fn set_credentials_provider<P>(&mut self, provider: P)
+    where
+        P: CredentialsProvider + 'static,
+    {
+        self.credentials = Box::new(provider);
+    }
+
     /// Send a request
     ///
+    /// The request is sent once, use [S3Client::send_builder] to retry
+    /// transient errors.
+    ///
     /// # Examples
     /// ```
     /// let client = S3Client::new_from_env();
@@ -132,7 +362,8 @@ impl S3Client {
     ///     key: "myobject".to_string(),
     ///     part_number: None,
     ///     version_id: None,
-    /// })?
+    ///     checksum_mode: false,
+    /// }).await?
     /// .query("versionId", "myversionid")
     /// .build()?;
     ///
@@ -146,20 +377,210 @@ impl S3Client {
         S3Response::from_response(resp)
     }
 
+    /// Build, sign and send a request, retrying transient errors
+    ///
+    /// The request is built and signed again for every attempt since the
+    /// signature includes the request time. Responses with a retryable status,
+    /// see [retry::is_retryable_status], and connection errors are retried
+    /// according to the [retry::RetryPolicy] as long as the client retry token
+    /// bucket has tokens left. The last response is returned if all attempts fail.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use s3_wasi_http::{S3Client, api::get_object::GetObjectRequest};
+    /// # async fn example() -> anyhow::Result<()> {
+    /// let client = S3Client::new_from_env();
+    ///
+    /// let mut builder = client
+    ///     .new_request_builder(GetObjectRequest::from_key("myobject"))
+    ///     .await?;
+    /// builder.query("versionId", Some("myversionid"));
+    ///
+    /// let resp = client.send_builder(builder).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_builder<T>(&self, mut builder: S3RequestBuilder<T>) -> Result<S3Response<T>>
+    where
+        T: S3ResponseData,
+    {
+        let mut attempt = 1;
+        let mut retry_cost = None;
+        loop {
+            let result = self.client.send(builder.build()?.request).await;
+            let reason = match &result {
+                Ok(resp) if is_retryable_status(resp.status()) => {
+                    RetryReason::Status(resp.status(), get_retry_after(resp.headers()))
+                }
+                Ok(_) => {
+                    self.retry_tokens.release(retry_cost);
+                    return S3Response::from_response(result?);
+                }
+                Err(_) => RetryReason::ConnectionError,
+            };
+
+            if attempt >= self.retry_policy.get_max_attempts() {
+                return S3Response::from_response(result?);
+            }
+            retry_cost = match self.retry_tokens.acquire(&reason) {
+                Some(cost) => Some(cost),
+                None => return S3Response::from_response(result?),
+            };
+            drop(result);
+
+            wstd::task::sleep(self.retry_policy.get_delay(attempt, &reason).into()).await;
+            attempt += 1;
+        }
+    }
+
+    /// Send a request with a streamed body
+    ///
+    /// The body can't be replayed so the request is sent once, see
+    /// [S3RequestBuilder::build_streamed].
+    pub async fn send_streamed<T, R>(
+        &self,
+        request: S3StreamedRequest<T, R>,
+    ) -> Result<S3Response<T>>
+    where
+        T: S3ResponseData,
+        R: AsyncRead,
+    {
+        let resp = self.client.send(request.request).await?;
+        S3Response::from_response(resp)
+    }
+
     /// Create a request builder from a request
     ///
-    /// Sets the access_key, secret_key, region and endpoint from the S3Client.
-    pub fn new_request_builder<T>(&self, request: T) -> Result<S3RequestBuilder<T::ResponseType>>
+    /// Sets the credentials from the credentials provider and the region and
+    /// endpoint from the S3Client.
+    pub async fn new_request_builder<T>(
+        &self,
+        request: T,
+    ) -> Result<S3RequestBuilder<T::ResponseType>>
+    where
+        T: S3RequestData,
+        <T as S3RequestData>::ResponseType: S3ResponseData,
+    {
+        let credentials = self.credentials.provide_credentials().await?;
+        let mut builder = request.into_builder(
+            &credentials.access_key,
+            &credentials.secret_key,
+            &self.region,
+            &self.endpoint,
+        )?;
+        builder.session_token = credentials.session_token;
+        builder.addressing_style = self.addressing_style.clone();
+        if let Some(bucket) = &self.bucket {
+            builder.bucket(bucket);
+        }
+
+        Ok(builder)
+    }
+
+    /// Send a request to `bucket` instead of the bucket of the client
+    ///
+    /// The endpoint has to be a service endpoint, see [S3Client::set_bucket].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use s3_wasi_http::{S3Client, api::get_object::GetObjectRequest};
+    /// # async fn example(client: S3Client) -> anyhow::Result<()> {
+    /// let object = client
+    ///     .send_to_bucket("otherbucket", GetObjectRequest::from_key("myobject"))
+    ///     .await?
+    ///     .into_response_data()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_to_bucket<T>(
+        &self,
+        bucket: &str,
+        request: T,
+    ) -> Result<S3Response<T::ResponseType>>
     where
         T: S3RequestData,
         <T as S3RequestData>::ResponseType: S3ResponseData,
     {
-        request.into_builder(
-            &self.access_key,
-            &self.secret_key,
+        let mut builder = self.new_request_builder(request).await?;
+        builder.bucket(bucket);
+
+        self.send_builder(builder).await
+    }
+
+    /// Create a presigned url for a request
+    ///
+    /// The url can be handed to a browser or another service to perform the
+    /// request without credentials until it expires, see [S3RequestBuilder::presign].
+    /// Use [S3Client::new_request_builder] to sign additional headers.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use s3_wasi_http::{S3Client, api::get_object::GetObjectRequest};
+    /// # async fn example() -> anyhow::Result<()> {
+    /// use std::time::Duration;
+    ///
+    /// let client = S3Client::new_from_env();
+    ///
+    /// let url = client
+    ///     .presign(
+    ///         GetObjectRequest::from_key("myobject"),
+    ///         Duration::from_secs(15 * 60),
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn presign<T>(&self, request: T, expires_in: Duration) -> Result<String>
+    where
+        T: S3RequestData,
+        <T as S3RequestData>::ResponseType: S3ResponseData,
+    {
+        self.new_request_builder(request).await?.presign(expires_in)
+    }
+
+    /// Sign a POST policy for browser based uploads
+    ///
+    /// see [api::post_policy::PostPolicy]
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use s3_wasi_http::{S3Client, api::post_policy::PostPolicy};
+    /// # async fn example() -> anyhow::Result<()> {
+    /// use std::time::Duration;
+    ///
+    /// let client = S3Client::new_from_env();
+    ///
+    /// let form = client
+    ///     .presigned_post(
+    ///         &PostPolicy::new(Duration::from_secs(10 * 60))
+    ///             .key_starts_with("uploads/")
+    ///             .content_length_range(1, 10 * 1024 * 1024),
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn presigned_post(&self, policy: &PostPolicy) -> Result<PostPolicyForm> {
+        let credentials = self.credentials.provide_credentials().await?;
+        let mut form = policy.build(
+            &credentials.access_key,
+            &credentials.secret_key,
+            credentials.session_token.as_deref(),
             &self.region,
             &self.endpoint,
-        )
+        )?;
+        if let Some(bucket) = &self.bucket {
+            let (scheme, host, path) = get_bucket_scheme_host_path(
+                &self.endpoint,
+                &Scheme::HTTPS,
+                Some(bucket),
+                &self.addressing_style,
+            )?;
+            form.url = format!("{scheme}://{host}{path}/");
+        }
+
+        Ok(form)
     }
 
     /// Send a head_object request
@@ -182,16 +603,9 @@ impl S3Client {
         &self,
         request: HeadObjectRequest,
     ) -> Result<S3Response<HeadObjectResponse>> {
-        let req = request
-            .into_builder(
-                &self.access_key,
-                &self.secret_key,
-                &self.region,
-                &self.endpoint,
-            )?
-            .build()?;
+        let builder = self.new_request_builder(request).await?;
 
-        self.send(req).await
+        self.send_builder(builder).await
     }
 
     /// Send a get_object request
@@ -218,16 +632,9 @@ impl S3Client {
         &self,
         request: GetObjectRequest,
     ) -> Result<S3Response<GetObjectResponse>> {
-        let req = request
-            .into_builder(
-                &self.access_key,
-                &self.secret_key,
-                &self.region,
-                &self.endpoint,
-            )?
-            .build()?;
+        let builder = self.new_request_builder(request).await?;
 
-        self.send(req).await
+        self.send_builder(builder).await
     }
 
     /// Send a list_buckets request
@@ -253,16 +660,9 @@ impl S3Client {
         &self,
         request: ListBucketsRequest,
     ) -> Result<S3Response<ListBucketsResponse>> {
-        let req = request
-            .into_builder(
-                &self.access_key,
-                &self.secret_key,
-                &self.region,
-                &self.endpoint,
-            )?
-            .build()?;
+        let builder = self.new_request_builder(request).await?;
 
-        self.send(req).await
+        self.send_builder(builder).await
     }
 
     /// Send a list_objects_v2 request
@@ -288,16 +688,9 @@ impl S3Client {
         &self,
         request: ListObjectsV2Request,
     ) -> Result<S3Response<ListObjectsV2Response>> {
-        let req = request
-            .into_builder(
-                &self.access_key,
-                &self.secret_key,
-                &self.region,
-                &self.endpoint,
-            )?
-            .build()?;
+        let builder = self.new_request_builder(request).await?;
 
-        self.send(req).await
+        self.send_builder(builder).await
     }
 
     /// Send a put_object request
@@ -318,25 +711,566 @@ impl S3Client {
     /// let mut contents = Vec::new();
     /// file.read_to_end(&mut contents);
     ///
-    /// let resp = self.list_buckets(PutObjectRequest {
-    ///     key: "myobject".to_string(),
-    ///     body: contents
-    /// })?;
+    /// let resp = self.put_object(PutObjectRequest::new("myobject", contents))?;
     /// assert_eq(resp.status(), StatusCode::OK);
     /// ```
     pub async fn put_object(
         &self,
         request: PutObjectRequest,
     ) -> Result<S3Response<PutObjectResponse>> {
-        let req = request
-            .into_builder(
-                &self.access_key,
-                &self.secret_key,
-                &self.region,
-                &self.endpoint,
-            )?
-            .build()?;
+        let builder = self.new_request_builder(request).await?;
+
+        self.send_builder(builder).await
+    }
+
+    /// Send a put_object request with the object read from `body`
+    /// see [api::put_object::PutObjectRequest], `request.body` is ignored
+    ///
+    /// The object is streamed without buffering it, `content_length` has to be
+    /// the exact size of the object. The payload is signed with `payload_hash`,
+    /// the hex encoded SHA-256 of the object, or sent unsigned if it's `None`.
+    /// see [S3RequestBuilder::build_streamed]
+    ///
+    /// returns [api::put_object::PutObjectResponse]
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use s3_wasi_http::{S3Client, api::put_object::PutObjectRequest};
+    /// # async fn example(request_body: wstd::http::body::IncomingBody, content_length: u64) -> anyhow::Result<()> {
+    /// let client = S3Client::new_from_env();
+    ///
+    /// let resp = client
+    ///     .put_object_streamed(
+    ///         PutObjectRequest::new("myobject", Vec::new()),
+    ///         request_body,
+    ///         content_length,
+    ///         None,
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn put_object_streamed<R>(
+        &self,
+        request: PutObjectRequest,
+        body: R,
+        content_length: u64,
+        payload_hash: Option<&str>,
+    ) -> Result<S3Response<PutObjectResponse>>
+    where
+        R: AsyncRead,
+    {
+        let req = self.new_request_builder(request).await?.build_streamed(
+            body,
+            content_length,
+            payload_hash,
+        )?;
+
+        self.send_streamed(req).await
+    }
+
+    /// Send a put_object request with the object read from `body`, signed
+    /// chunk by chunk
+    /// see [api::put_object::PutObjectRequest], `request.body` is ignored
+    ///
+    /// The object is streamed with the aws-chunked content encoding, every
+    /// chunk is signed so the payload is verified without hashing the whole
+    /// object first. `content_length` has to be the exact size of the object.
+    /// see [S3RequestBuilder::build_aws_chunked]
+    ///
+    /// returns [api::put_object::PutObjectResponse]
+    pub async fn put_object_chunked<R>(
+        &self,
+        request: PutObjectRequest,
+        body: R,
+        content_length: u64,
+    ) -> Result<S3Response<PutObjectResponse>>
+    where
+        R: AsyncRead,
+    {
+        let req = self.new_request_builder(request).await?.build_aws_chunked(
+            body,
+            content_length,
+            DEFAULT_CHUNK_SIZE,
+        )?;
+
+        self.send_streamed(req).await
+    }
+
+    /// Send a put_object request with the object read from `body` and a
+    /// trailing checksum
+    /// see [api::put_object::PutObjectRequest], `request.body` is ignored
+    ///
+    /// The object is streamed unsigned with the aws-chunked content encoding,
+    /// the checksum of `algorithm` is computed while streaming and sent as a
+    /// trailer for S3 to verify. `content_length` has to be the exact size of
+    /// the object.
+    /// see [S3RequestBuilder::build_aws_chunked_trailer]
+    ///
+    /// returns [api::put_object::PutObjectResponse]
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use s3_wasi_http::{S3Client, api::{ChecksumAlgorithm, put_object::PutObjectRequest}};
+    /// # async fn example(request_body: wstd::http::body::IncomingBody, content_length: u64) -> anyhow::Result<()> {
+    /// let client = S3Client::new_from_env();
+    ///
+    /// let resp = client
+    ///     .put_object_trailing_checksum(
+    ///         PutObjectRequest::new("myobject", Vec::new()),
+    ///         request_body,
+    ///         content_length,
+    ///         &ChecksumAlgorithm::CRC32C,
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn put_object_trailing_checksum<R>(
+        &self,
+        request: PutObjectRequest,
+        body: R,
+        content_length: u64,
+        algorithm: &ChecksumAlgorithm,
+    ) -> Result<S3Response<PutObjectResponse>>
+    where
+        R: AsyncRead,
+    {
+        let req = self
+            .new_request_builder(request)
+            .await?
+            .build_aws_chunked_trailer(body, content_length, DEFAULT_CHUNK_SIZE, algorithm)?;
+
+        self.send_streamed(req).await
+    }
+
+    /// Send a create_multipart_upload request
+    /// see [api::create_multipart_upload::CreateMultipartUploadRequest]
+    ///
+    /// returns [api::create_multipart_upload::CreateMultipartUploadResponse]
+    ///
+    /// if you need to set any specific headers or query string values look at
+    /// [S3Client::send] and [S3Client::new_request_builder]
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use s3_wasi_http::{S3Client, api::create_multipart_upload::CreateMultipartUploadRequest};
+    /// # async fn example() -> anyhow::Result<()> {
+    /// let client = S3Client::new_from_env();
+    ///
+    /// let mut resp = client
+    ///     .create_multipart_upload(CreateMultipartUploadRequest::from_key("myobject"))
+    ///     .await?;
+    /// let upload_id = resp.into_response_data().await?.upload_id;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_multipart_upload(
+        &self,
+        request: CreateMultipartUploadRequest,
+    ) -> Result<S3Response<CreateMultipartUploadResponse>> {
+        let builder = self.new_request_builder(request).await?;
+
+        self.send_builder(builder).await
+    }
+
+    /// Send an upload_part request
+    /// see [api::upload_part::UploadPartRequest]
+    ///
+    /// returns [api::upload_part::UploadPartResponse], the part ETag is
+    /// returned in the response headers, see
+    /// [api::complete_multipart_upload::CompletedPart::from_headers]
+    ///
+    /// if you need to set any specific headers or query string values look at
+    /// [S3Client::send] and [S3Client::new_request_builder]
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use s3_wasi_http::{S3Client, api::{upload_part::UploadPartRequest, complete_multipart_upload::CompletedPart}};
+    /// # async fn example(upload_id: String, contents: Vec<u8>) -> anyhow::Result<()> {
+    /// let client = S3Client::new_from_env();
+    ///
+    /// let mut resp = client
+    ///     .upload_part(UploadPartRequest {
+    ///         key: "myobject".to_string(),
+    ///         upload_id,
+    ///         part_number: 1,
+    ///         body: contents,
+    ///     })
+    ///     .await?;
+    /// let (head, _) = resp.into_response_data_parts().await?;
+    /// let part = CompletedPart::from_headers(1, &head)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn upload_part(
+        &self,
+        request: UploadPartRequest,
+    ) -> Result<S3Response<UploadPartResponse>> {
+        let builder = self.new_request_builder(request).await?;
+
+        self.send_builder(builder).await
+    }
+
+    /// Send an upload_part request with the part read from `body`
+    /// see [api::upload_part::UploadPartRequest], `request.body` is ignored
+    ///
+    /// The part is streamed without buffering it, `content_length` has to be
+    /// the exact size of the part. The payload is signed with `payload_hash`,
+    /// the hex encoded SHA-256 of the part, or sent unsigned if it's `None`.
+    /// see [S3RequestBuilder::build_streamed]
+    ///
+    /// returns [api::upload_part::UploadPartResponse]
+    pub async fn upload_part_streamed<R>(
+        &self,
+        request: UploadPartRequest,
+        body: R,
+        content_length: u64,
+        payload_hash: Option<&str>,
+    ) -> Result<S3Response<UploadPartResponse>>
+    where
+        R: AsyncRead,
+    {
+        let req = self.new_request_builder(request).await?.build_streamed(
+            body,
+            content_length,
+            payload_hash,
+        )?;
+
+        self.send_streamed(req).await
+    }
+
+    /// Send an upload_part request with the part read from `body`, signed
+    /// chunk by chunk
+    /// see [api::upload_part::UploadPartRequest], `request.body` is ignored
+    ///
+    /// `content_length` has to be the exact size of the part.
+    /// see [S3Client::put_object_chunked]
+    ///
+    /// returns [api::upload_part::UploadPartResponse]
+    pub async fn upload_part_chunked<R>(
+        &self,
+        request: UploadPartRequest,
+        body: R,
+        content_length: u64,
+    ) -> Result<S3Response<UploadPartResponse>>
+    where
+        R: AsyncRead,
+    {
+        let req = self.new_request_builder(request).await?.build_aws_chunked(
+            body,
+            content_length,
+            DEFAULT_CHUNK_SIZE,
+        )?;
+
+        self.send_streamed(req).await
+    }
+
+    /// Send an upload_part request with the part read from `body` and a
+    /// trailing checksum
+    /// see [api::upload_part::UploadPartRequest], `request.body` is ignored
+    ///
+    /// The upload has to be created with the same
+    /// [api::create_multipart_upload::CreateMultipartUploadRequest::checksum_algorithm].
+    /// `content_length` has to be the exact size of the part.
+    /// see [S3Client::put_object_trailing_checksum]
+    ///
+    /// returns [api::upload_part::UploadPartResponse]
+    pub async fn upload_part_trailing_checksum<R>(
+        &self,
+        request: UploadPartRequest,
+        body: R,
+        content_length: u64,
+        algorithm: &ChecksumAlgorithm,
+    ) -> Result<S3Response<UploadPartResponse>>
+    where
+        R: AsyncRead,
+    {
+        let req = self
+            .new_request_builder(request)
+            .await?
+            .build_aws_chunked_trailer(body, content_length, DEFAULT_CHUNK_SIZE, algorithm)?;
+
+        self.send_streamed(req).await
+    }
+
+    /// Send a complete_multipart_upload request
+    /// see [api::complete_multipart_upload::CompleteMultipartUploadRequest]
+    ///
+    /// returns [api::complete_multipart_upload::CompleteMultipartUploadResponse]
+    ///
+    /// if you need to set any specific headers or query string values look at
+    /// [S3Client::send] and [S3Client::new_request_builder]
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use s3_wasi_http::{S3Client, api::complete_multipart_upload::{CompleteMultipartUploadRequest, CompletedPart}};
+    /// # async fn example(upload_id: String, parts: Vec<CompletedPart>) -> anyhow::Result<()> {
+    /// let client = S3Client::new_from_env();
+    ///
+    /// let mut resp = client
+    ///     .complete_multipart_upload(CompleteMultipartUploadRequest {
+    ///         key: "myobject".to_string(),
+    ///         upload_id,
+    ///         parts,
+    ///     })
+    ///     .await?;
+    /// let etag = resp.into_response_data().await?.etag;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn complete_multipart_upload(
+        &self,
+        request: CompleteMultipartUploadRequest,
+    ) -> Result<S3Response<CompleteMultipartUploadResponse>> {
+        let builder = self.new_request_builder(request).await?;
+
+        self.send_builder(builder).await
+    }
+
+    /// Send an abort_multipart_upload request
+    /// see [api::abort_multipart_upload::AbortMultipartUploadRequest]
+    ///
+    /// returns [api::abort_multipart_upload::AbortMultipartUploadResponse]
+    ///
+    /// if you need to set any specific headers or query string values look at
+    /// [S3Client::send] and [S3Client::new_request_builder]
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use s3_wasi_http::{S3Client, api::abort_multipart_upload::AbortMultipartUploadRequest};
+    /// # async fn example(upload_id: String) -> anyhow::Result<()> {
+    /// use http::StatusCode;
+    ///
+    /// let client = S3Client::new_from_env();
+    ///
+    /// let resp = client
+    ///     .abort_multipart_upload(AbortMultipartUploadRequest {
+    ///         key: "myobject".to_string(),
+    ///         upload_id,
+    ///     })
+    ///     .await?;
+    /// assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn abort_multipart_upload(
+        &self,
+        request: AbortMultipartUploadRequest,
+    ) -> Result<S3Response<AbortMultipartUploadResponse>> {
+        let builder = self.new_request_builder(request).await?;
+
+        self.send_builder(builder).await
+    }
+
+    /// Send a list_parts request
+    /// see [api::list_parts::ListPartsRequest]
+    ///
+    /// returns [api::list_parts::ListPartsResponse]
+    ///
+    /// if you need to set any specific headers or query string values look at
+    /// [S3Client::send] and [S3Client::new_request_builder]
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use s3_wasi_http::{S3Client, api::list_parts::ListPartsRequest};
+    /// # async fn example(upload_id: String) -> anyhow::Result<()> {
+    /// let client = S3Client::new_from_env();
+    ///
+    /// let mut parts = Vec::new();
+    /// let mut request = Some(ListPartsRequest::new("myobject", &upload_id));
+    /// while let Some(req) = request {
+    ///     let data = client.list_parts(req).await?.into_response_data().await?;
+    ///     request = data.next_page();
+    ///     parts.extend(data.parts);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_parts(
+        &self,
+        request: ListPartsRequest,
+    ) -> Result<S3Response<ListPartsResponse>> {
+        let builder = self.new_request_builder(request).await?;
+
+        self.send_builder(builder).await
+    }
+
+    /// Send a list_multipart_uploads request
+    /// see [api::list_multipart_uploads::ListMultipartUploadsRequest]
+    ///
+    /// returns [api::list_multipart_uploads::ListMultipartUploadsResponse]
+    ///
+    /// if you need to set any specific headers or query string values look at
+    /// [S3Client::send] and [S3Client::new_request_builder]
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use s3_wasi_http::{S3Client, api::{list_multipart_uploads::ListMultipartUploadsRequest, abort_multipart_upload::AbortMultipartUploadRequest}};
+    /// # async fn example() -> anyhow::Result<()> {
+    /// let client = S3Client::new_from_env();
+    ///
+    /// let mut request = Some(ListMultipartUploadsRequest::default());
+    /// while let Some(req) = request {
+    ///     let data = client
+    ///         .list_multipart_uploads(req)
+    ///         .await?
+    ///         .into_response_data()
+    ///         .await?;
+    ///     request = data.next_page();
+    ///
+    ///     for upload in data.uploads {
+    ///         client
+    ///             .abort_multipart_upload(AbortMultipartUploadRequest {
+    ///                 key: upload.key,
+    ///                 upload_id: upload.upload_id,
+    ///             })
+    ///             .await?;
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_multipart_uploads(
+        &self,
+        request: ListMultipartUploadsRequest,
+    ) -> Result<S3Response<ListMultipartUploadsResponse>> {
+        let builder = self.new_request_builder(request).await?;
+
+        self.send_builder(builder).await
+    }
+
+    /// Send a delete_object request
+    /// see [api::delete_object::DeleteObjectRequest]
+    ///
+    /// returns [api::delete_object::DeleteObjectResponse]
+    ///
+    /// if you need to set any specific headers or query string values look at
+    /// [S3Client::send] and [S3Client::new_request_builder]
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use s3_wasi_http::{S3Client, api::delete_object::DeleteObjectRequest};
+    /// # async fn example() -> anyhow::Result<()> {
+    /// use http::StatusCode;
+    ///
+    /// let client = S3Client::new_from_env();
+    ///
+    /// let resp = client
+    ///     .delete_object(DeleteObjectRequest::from_key("myobject"))
+    ///     .await?;
+    /// assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete_object(
+        &self,
+        request: DeleteObjectRequest,
+    ) -> Result<S3Response<DeleteObjectResponse>> {
+        let builder = self.new_request_builder(request).await?;
 
-        self.send(req).await
+        self.send_builder(builder).await
     }
-}
\ No newline at end of file
+
+    /// Send a delete_objects request
+    /// see [api::delete_objects::DeleteObjectsRequest]
+    ///
+    /// returns [api::delete_objects::DeleteObjectsResponse]
+    ///
+    /// if you need to set any specific headers or query string values look at
+    /// [S3Client::send] and [S3Client::new_request_builder]
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use s3_wasi_http::{S3Client, api::delete_objects::DeleteObjectsRequest};
+    /// # async fn example() -> anyhow::Result<()> {
+    /// let client = S3Client::new_from_env();
+    ///
+    /// let mut resp = client
+    ///     .delete_objects(DeleteObjectsRequest::from_keys(&["object1", "object2"]))
+    ///     .await?;
+    /// for error in resp.into_response_data().await?.errors {
+    ///     println!("failed to delete {}: {}", error.key, error.message);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete_objects(
+        &self,
+        request: DeleteObjectsRequest,
+    ) -> Result<S3Response<DeleteObjectsResponse>> {
+        let builder = self.new_request_builder(request).await?;
+
+        self.send_builder(builder).await
+    }
+
+    /// Send a copy_object request
+    /// see [api::copy_object::CopyObjectRequest]
+    ///
+    /// returns [api::copy_object::CopyObjectResponse]
+    ///
+    /// if you need to set any specific headers or query string values look at
+    /// [S3Client::send] and [S3Client::new_request_builder]
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use s3_wasi_http::{S3Client, api::copy_object::{CopyObjectRequest, CopySource}};
+    /// # async fn example() -> anyhow::Result<()> {
+    /// let client = S3Client::new_from_env();
+    ///
+    /// let mut resp = client
+    ///     .copy_object(CopyObjectRequest::new(
+    ///         "archive/myobject",
+    ///         CopySource::new("mybucket", "incoming/myobject"),
+    ///     ))
+    ///     .await?;
+    /// let etag = resp.into_response_data().await?.etag;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn copy_object(
+        &self,
+        request: CopyObjectRequest,
+    ) -> Result<S3Response<CopyObjectResponse>> {
+        let builder = self.new_request_builder(request).await?;
+
+        self.send_builder(builder).await
+    }
+
+    /// Send an upload_part_copy request
+    /// see [api::upload_part_copy::UploadPartCopyRequest]
+    ///
+    /// returns [api::upload_part_copy::UploadPartCopyResponse]
+    ///
+    /// if you need to set any specific headers or query string values look at
+    /// [S3Client::send] and [S3Client::new_request_builder]
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use s3_wasi_http::{S3Client, api::{copy_object::CopySource, upload_part_copy::UploadPartCopyRequest}};
+    /// # async fn example(upload_id: String) -> anyhow::Result<()> {
+    /// let client = S3Client::new_from_env();
+    ///
+    /// let mut resp = client
+    ///     .upload_part_copy(UploadPartCopyRequest {
+    ///         key: "myobject".to_string(),
+    ///         upload_id,
+    ///         part_number: 1,
+    ///         source: CopySource::new("mybucket", "mylargeobject"),
+    ///         source_range: Some((0, 5 * 1024 * 1024 * 1024 - 1)),
+    ///         source_conditions: None,
+    ///     })
+    ///     .await?;
+    /// let part = resp.into_response_data().await?.into_completed_part(1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn upload_part_copy(
+        &self,
+        request: UploadPartCopyRequest,
+    ) -> Result<S3Response<UploadPartCopyResponse>> {
+        let builder = self.new_request_builder(request).await?;
+
+        self.send_builder(builder).await
+    }
+}
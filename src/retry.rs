@@ -0,0 +1,303 @@
+use std::{cell::Cell, time::Duration};
+
+use chrono::{DateTime, Utc};
+use http::{HeaderMap, StatusCode};
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(100);
+const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(20);
+
+const TOKEN_BUCKET_CAPACITY: u32 = 500;
+const TOKEN_BUCKET_RETRY_COST: u32 = 5;
+const TOKEN_BUCKET_CONNECTION_ERROR_COST: u32 = 10;
+const TOKEN_BUCKET_SUCCESS_REFUND: u32 = 1;
+
+/// Why a request attempt failed
+pub enum RetryReason {
+    /// A retryable status code, with the delay from the Retry-After header
+    Status(StatusCode, Option<Duration>),
+    /// The request failed before a response was received
+    ConnectionError,
+}
+
+/// Check if a response status is a transient error worth retrying
+///
+/// 500 InternalError, 503 SlowDown and friends, 429 and gateway errors.
+pub fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Get the delay from a Retry-After header, in seconds or as an http date
+pub(crate) fn get_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get("retry-after")?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?.to_utc();
+    (date - Utc::now()).to_std().ok()
+}
+
+/// How failed requests are retried by [crate::S3Client::send_builder]
+///
+/// Attempts are delayed with exponential backoff and full jitter, a random
+/// delay between 0 and `min(max_delay, base_delay * 2^(attempt - 1))`. A Retry-After
+/// header overrides the backoff, capped at `max_delay`.
+///
+/// # Examples
+/// ```no_run
+/// # use s3_wasi_http::{S3Client, retry::RetryPolicy};
+/// use std::time::Duration;
+///
+/// let mut client = S3Client::new_from_env();
+/// client.set_retry_policy(
+///     RetryPolicy::default()
+///         .max_attempts(5)
+///         .max_delay(Duration::from_secs(5)),
+/// );
+/// ```
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: DEFAULT_BASE_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry a request
+    pub fn disabled() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// The maximum number of attempts including the first one
+    pub fn max_attempts(self, max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..self
+        }
+    }
+    pub fn base_delay(self, base_delay: Duration) -> Self {
+        Self { base_delay, ..self }
+    }
+    pub fn max_delay(self, max_delay: Duration) -> Self {
+        Self { max_delay, ..self }
+    }
+    /// Use full jitter, enabled by default
+    pub fn jitter(self, jitter: bool) -> Self {
+        Self { jitter, ..self }
+    }
+
+    pub(crate) fn get_max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// The delay before retrying after `attempt` failed attempts
+    pub(crate) fn get_delay(&self, attempt: u32, reason: &RetryReason) -> Duration {
+        if let RetryReason::Status(_, Some(retry_after)) = reason {
+            return (*retry_after).min(self.max_delay);
+        }
+
+        let backoff = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay);
+        match self.jitter {
+            true => backoff.mul_f64(get_random_fraction()),
+            false => backoff,
+        }
+    }
+}
+
+/// A random value in [0, 1)
+fn get_random_fraction() -> f64 {
+    let mut bytes = [0u8; 8];
+    wstd::rand::get_insecure_random_bytes(&mut bytes);
+    (u64::from_le_bytes(bytes) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// A client side token bucket limiting retries
+///
+/// Every retry takes tokens from the bucket and successful requests return
+/// them, so when a dependency keeps failing the retries stop instead of
+/// multiplying the load.
+pub struct RetryTokenBucket {
+    tokens: Cell<u32>,
+}
+
+impl Default for RetryTokenBucket {
+    fn default() -> Self {
+        Self {
+            tokens: Cell::new(TOKEN_BUCKET_CAPACITY),
+        }
+    }
+}
+
+impl RetryTokenBucket {
+    /// Take the tokens for a retry, returns `None` if the bucket is empty
+    pub(crate) fn acquire(&self, reason: &RetryReason) -> Option<u32> {
+        let cost = match reason {
+            RetryReason::ConnectionError => TOKEN_BUCKET_CONNECTION_ERROR_COST,
+            RetryReason::Status(..) => TOKEN_BUCKET_RETRY_COST,
+        };
+        let tokens = self.tokens.get();
+        if tokens < cost {
+            return None;
+        }
+
+        self.tokens.set(tokens - cost);
+        Some(cost)
+    }
+
+    /// Return tokens after a successful request
+    pub(crate) fn release(&self, retry_cost: Option<u32>) {
+        let refund = retry_cost.unwrap_or(TOKEN_BUCKET_SUCCESS_REFUND);
+        self.tokens
+            .set((self.tokens.get() + refund).min(TOKEN_BUCKET_CAPACITY));
+    }
+
+    /// The number of tokens available
+    pub fn available(&self) -> u32 {
+        self.tokens.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderValue;
+
+    use super::*;
+
+    fn get_headers(retry_after: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_str(retry_after).unwrap());
+        headers
+    }
+
+    #[test]
+    fn backoff() {
+        let policy = RetryPolicy::default()
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_secs(1))
+            .jitter(false);
+        let reason = RetryReason::ConnectionError;
+
+        assert_eq!(policy.get_delay(1, &reason), Duration::from_millis(100));
+        assert_eq!(policy.get_delay(2, &reason), Duration::from_millis(200));
+        assert_eq!(policy.get_delay(4, &reason), Duration::from_millis(800));
+        // Capped at max_delay, also when 2^attempt overflows
+        assert_eq!(policy.get_delay(5, &reason), Duration::from_secs(1));
+        assert_eq!(policy.get_delay(100, &reason), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn retry_after_overrides_backoff() {
+        let policy = RetryPolicy::default()
+            .max_delay(Duration::from_secs(5))
+            .jitter(false);
+
+        let reason = RetryReason::Status(
+            StatusCode::SERVICE_UNAVAILABLE,
+            Some(Duration::from_secs(2)),
+        );
+        assert_eq!(policy.get_delay(1, &reason), Duration::from_secs(2));
+        let reason = RetryReason::Status(
+            StatusCode::SERVICE_UNAVAILABLE,
+            Some(Duration::from_secs(60)),
+        );
+        assert_eq!(policy.get_delay(1, &reason), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn parse_retry_after() {
+        assert_eq!(
+            get_retry_after(&get_headers("120")),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(get_retry_after(&HeaderMap::new()), None);
+        assert_eq!(get_retry_after(&get_headers("soon")), None);
+        // Dates in the past are no delay
+        assert_eq!(
+            get_retry_after(&get_headers("Wed, 21 Oct 2015 07:28:00 GMT")),
+            None
+        );
+
+        let date = (Utc::now() + chrono::Duration::seconds(60)).to_rfc2822();
+        let delay = get_retry_after(&get_headers(&date)).unwrap();
+        assert!(delay > Duration::from_secs(55) && delay <= Duration::from_secs(60));
+    }
+
+    #[test]
+    fn retryable_status() {
+        assert!(is_retryable_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+        assert!(!is_retryable_status(StatusCode::NOT_IMPLEMENTED));
+    }
+
+    #[test]
+    fn token_bucket_exhaustion() {
+        let bucket = RetryTokenBucket::default();
+        let reason = RetryReason::ConnectionError;
+        for _ in 0..TOKEN_BUCKET_CAPACITY / TOKEN_BUCKET_CONNECTION_ERROR_COST {
+            assert_eq!(
+                bucket.acquire(&reason),
+                Some(TOKEN_BUCKET_CONNECTION_ERROR_COST)
+            );
+        }
+
+        assert_eq!(bucket.available(), 0);
+        assert_eq!(bucket.acquire(&reason), None);
+        assert_eq!(
+            bucket.acquire(&RetryReason::Status(StatusCode::SERVICE_UNAVAILABLE, None)),
+            None
+        );
+    }
+
+    #[test]
+    fn token_bucket_refund() {
+        let bucket = RetryTokenBucket::default();
+        let reason = RetryReason::Status(StatusCode::SERVICE_UNAVAILABLE, None);
+
+        // A successful retry returns its cost
+        let cost = bucket.acquire(&reason);
+        assert_eq!(
+            bucket.available(),
+            TOKEN_BUCKET_CAPACITY - TOKEN_BUCKET_RETRY_COST
+        );
+        bucket.release(cost);
+        assert_eq!(bucket.available(), TOKEN_BUCKET_CAPACITY);
+
+        // A success without retries refunds a token, up to the capacity
+        bucket.acquire(&reason);
+        bucket.release(None);
+        assert_eq!(
+            bucket.available(),
+            TOKEN_BUCKET_CAPACITY - TOKEN_BUCKET_RETRY_COST + TOKEN_BUCKET_SUCCESS_REFUND
+        );
+        bucket.release(None);
+        bucket.release(Some(TOKEN_BUCKET_RETRY_COST));
+        assert_eq!(bucket.available(), TOKEN_BUCKET_CAPACITY);
+    }
+}